@@ -0,0 +1,445 @@
+//! Benchmarking setup for pallet-kitties
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as KittiesPallet;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::Hooks;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+
+/// Give `who` enough funds to cover deposits and purchases.
+fn fund<T: Config>(who: &T::AccountId) {
+    let _ = T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+/// Store a kitty with the given DNA for `owner`, bypassing randomness.
+fn insert_kitty<T: Config>(owner: &T::AccountId, dna: [u8; 16]) -> u32 {
+    let kitty_id = NextKittyId::<T>::get();
+    Kitties::<T>::insert(kitty_id, Kitty(dna));
+    OwnerOf::<T>::insert(kitty_id, owner);
+    NextKittyId::<T>::put(kitty_id + 1);
+    KittiesOwned::<T>::mutate(owner, |ids| ids.try_push(kitty_id).expect("below cap"));
+    OwnedCount::<T>::mutate(owner, |count| *count += 1);
+    KittyCount::<T>::mutate(|count| *count += 1);
+    match KittyGender::from_dna(&dna) {
+        KittyGender::Male => MaleCount::<T>::mutate(|count| *count += 1),
+        KittyGender::Female => FemaleCount::<T>::mutate(|count| *count += 1),
+    }
+    kitty_id
+}
+
+/// Move on to `MinBlockForCreation` so randomness can be drawn.
+fn randomness_ready<T: Config>() {
+    frame_system::Pallet::<T>::set_block_number(T::MinBlockForCreation::get());
+}
+
+benchmarks! {
+    create {
+        randomness_ready::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_eq!(OwnerOf::<T>::get(0), Some(caller));
+    }
+
+    gift_random {
+        randomness_ready::<T>();
+        let to: T::AccountId = account("to", 0, SEED);
+        let origin = T::GiftOrigin::successful_origin();
+        // The gifter pays the deposit
+        let from = T::GiftOrigin::ensure_origin(origin.clone()).expect("successful origin");
+        fund::<T>(&from);
+    }: _<T::Origin>(origin, to.clone())
+    verify {
+        assert_eq!(OwnedCount::<T>::get(&to), 1);
+        assert_eq!(KittyDeposits::<T>::get(0).map(|(depositor, _)| depositor), Some(from));
+    }
+
+    create_batch {
+        randomness_ready::<T>();
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+    }: _(RawOrigin::Signed(caller.clone()), n)
+    verify {
+        assert_eq!(OwnedCount::<T>::get(&caller), n);
+    }
+
+    breed {
+        randomness_ready::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        // An even first byte is a male, an odd one a female.
+        let male = insert_kitty::<T>(&caller, [0u8; 16]);
+        let female = insert_kitty::<T>(&caller, [1u8; 16]);
+    }: _(RawOrigin::Signed(caller.clone()), male, female)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(2), Some(caller));
+    }
+
+    fuse {
+        randomness_ready::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let male = insert_kitty::<T>(&caller, [0u8; 16]);
+        let female = insert_kitty::<T>(&caller, [1u8; 16]);
+    }: _(RawOrigin::Signed(caller.clone()), male, female)
+    verify {
+        assert_eq!(OwnedCount::<T>::get(&caller), 1);
+    }
+
+    breed_batch {
+        randomness_ready::<T>();
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let mut pairs = Vec::new();
+        for i in 0 .. n as u8 {
+            let male = insert_kitty::<T>(&caller, [i.wrapping_mul(2); 16]);
+            let female = insert_kitty::<T>(&caller, [i.wrapping_mul(2) + 1; 16]);
+            pairs.push((male, female));
+        }
+        KittiesPallet::<T>::force_litter_size(KittiesPallet::<T>::max_litter_size());
+    }: _(RawOrigin::Signed(caller.clone()), pairs)
+    verify {
+        let litter = KittiesPallet::<T>::max_litter_size();
+        assert_eq!(OwnedCount::<T>::get(&caller), (2 + litter) * n);
+    }
+
+    approve_breeding {
+        let caller: T::AccountId = whitelisted_caller();
+        let partner: T::AccountId = account("partner", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, partner.clone())
+    verify {
+        assert_eq!(BreedingApprovals::<T>::get(&caller, kitty_id), Some(partner));
+    }
+
+    reserve_for_breeding {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let partner: T::AccountId = account("partner", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let partner_kitty_id = insert_kitty::<T>(&partner, [1u8; 16]);
+        let until_block: T::BlockNumber = 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, partner.clone(), partner_kitty_id, until_block)
+    verify {
+        assert_eq!(BreedingLocks::<T>::get(kitty_id), Some((partner_kitty_id, until_block)));
+    }
+
+    breed_with {
+        randomness_ready::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        let partner: T::AccountId = account("partner", 0, SEED);
+        fund::<T>(&caller);
+        let male = insert_kitty::<T>(&caller, [0u8; 16]);
+        let female = insert_kitty::<T>(&partner, [1u8; 16]);
+        BreedingApprovals::<T>::insert(&partner, female, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), male, partner, female)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(2), Some(caller));
+    }
+
+    transfer {
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller), recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(recipient));
+    }
+
+    transfer_all {
+        let n in 1 .. T::MaxKittiesOwned::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        for _ in 0..n {
+            insert_kitty::<T>(&caller, [0u8; 16]);
+        }
+    }: _(RawOrigin::Signed(caller), recipient.clone())
+    verify {
+        assert_eq!(OwnedCount::<T>::get(&recipient), n);
+    }
+
+    transfer_many {
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let kitty_ids: Vec<u32> = (0..n).map(|_| insert_kitty::<T>(&caller, [0u8; 16])).collect();
+    }: _(RawOrigin::Signed(caller), recipient.clone(), kitty_ids)
+    verify {
+        assert_eq!(OwnedCount::<T>::get(&recipient), n);
+    }
+
+    burn {
+        let a in 0 .. T::MaxAttributes::get();
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        for i in 0 .. a {
+            KittiesPallet::<T>::set_attribute(
+                RawOrigin::Signed(caller.clone()).into(),
+                kitty_id,
+                i.encode(),
+                sp_std::vec![b'v'; T::MaxValueLen::get() as usize],
+            )?;
+        }
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert!(!Kitties::<T>::contains_key(kitty_id));
+    }
+
+    cleanup_dead_kitties {
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller: T::AccountId = whitelisted_caller();
+        for kitty_id in 0..n {
+            KittiesPallet::<T>::bury(&caller, kitty_id, T::BlockNumber::from(0u32));
+        }
+        frame_system::Pallet::<T>::set_block_number(T::GraveyardRetention::get());
+    }: _(RawOrigin::Signed(caller), n)
+    verify {
+        assert_eq!(DeadKitties::<T>::iter().count(), 0);
+        assert_eq!(KittiesPallet::<T>::graveyard_range(), (n as u64, n as u64));
+    }
+
+    abandon {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert!(AbandonedKitties::<T>::contains_key(kitty_id));
+    }
+
+    claim {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let kitty_id = NextKittyId::<T>::get();
+        NextKittyId::<T>::put(kitty_id + 1);
+        AbandonedKitties::<T>::insert(kitty_id, Kitty([0u8; 16]));
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(caller));
+    }
+
+    set_name {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let name = sp_std::vec![b'k'; T::MaxNameLength::get() as usize];
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, name)
+    verify {
+        assert!(KittyNames::<T>::contains_key(&caller, kitty_id));
+    }
+
+    set_attribute {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let key = sp_std::vec![b'k'; T::MaxKeyLen::get() as usize];
+        let value = sp_std::vec![b'v'; T::MaxValueLen::get() as usize];
+    }: _(RawOrigin::Signed(caller), kitty_id, key, value)
+    verify {
+        assert_eq!(AttributeCount::<T>::get(kitty_id), 1);
+    }
+
+    clear_attribute {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let key = sp_std::vec![b'k'; T::MaxKeyLen::get() as usize];
+        let value = sp_std::vec![b'v'; T::MaxValueLen::get() as usize];
+        KittiesPallet::<T>::set_attribute(
+            RawOrigin::Signed(caller.clone()).into(),
+            kitty_id,
+            key.clone(),
+            value,
+        )?;
+    }: _(RawOrigin::Signed(caller), kitty_id, key)
+    verify {
+        assert_eq!(AttributeCount::<T>::get(kitty_id), 0);
+    }
+
+    set_sterile {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(SterileKitties::<T>::contains_key(kitty_id));
+    }
+
+    set_price {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let price = Some(100u32.into());
+        let expiry_block = Some(10u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, price, expiry_block)
+    verify {
+        assert_eq!(KittyPrices::<T>::get(&caller, kitty_id), price);
+        assert_eq!(ListingExpiry::<T>::get(&caller, kitty_id), expiry_block);
+    }
+
+    on_initialize {
+        let n in 0 .. T::MaxExpiringPerBlock::get();
+        let owner: T::AccountId = whitelisted_caller();
+        let expiry_block: T::BlockNumber = 10u32.into();
+        for kitty_id in 0..n {
+            KittyPrices::<T>::insert(&owner, kitty_id, BalanceOf::<T>::from(100u32));
+            ListingExpiry::<T>::insert(&owner, kitty_id, expiry_block);
+            ExpiringListings::<T>::try_mutate(expiry_block, |listings| {
+                listings.try_push((owner.clone(), kitty_id))
+            })
+            .expect("at most MaxExpiringPerBlock listings are queued");
+        }
+    }: {
+        KittiesPallet::<T>::on_initialize(expiry_block);
+    }
+    verify {
+        assert_eq!(KittyPrices::<T>::iter_prefix(&owner).count(), 0);
+    }
+
+    buy {
+        let caller: T::AccountId = whitelisted_caller();
+        let seller: T::AccountId = account("seller", 0, SEED);
+        fund::<T>(&caller);
+        fund::<T>(&seller);
+        let kitty_id = insert_kitty::<T>(&seller, [0u8; 16]);
+        let price: BalanceOf<T> = 100u32.into();
+        KittyPrices::<T>::insert(&seller, kitty_id, price);
+    }: _(RawOrigin::Signed(caller.clone()), seller, kitty_id, price)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(caller));
+    }
+
+    start_auction {
+        let caller: T::AccountId = whitelisted_caller();
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller), kitty_id, 100u32.into(), 10u32.into())
+    verify {
+        assert!(KittyAuctions::<T>::contains_key(kitty_id));
+    }
+
+    bid {
+        let caller: T::AccountId = whitelisted_caller();
+        let seller: T::AccountId = account("seller", 0, SEED);
+        let previous: T::AccountId = account("previous", 0, SEED);
+        fund::<T>(&caller);
+        fund::<T>(&previous);
+        let kitty_id = insert_kitty::<T>(&seller, [0u8; 16]);
+        KittiesPallet::<T>::start_auction(
+            RawOrigin::Signed(seller).into(),
+            kitty_id,
+            100u32.into(),
+            10u32.into(),
+        )?;
+        KittiesPallet::<T>::bid(RawOrigin::Signed(previous).into(), kitty_id, 100u32.into())?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, 200u32.into())
+    verify {
+        assert_eq!(KittyAuctions::<T>::get(kitty_id).unwrap().highest_bidder, Some(caller));
+    }
+
+    close_auction {
+        let caller: T::AccountId = whitelisted_caller();
+        let seller: T::AccountId = account("seller", 0, SEED);
+        let bidder: T::AccountId = account("bidder", 0, SEED);
+        fund::<T>(&bidder);
+        let kitty_id = insert_kitty::<T>(&seller, [0u8; 16]);
+        KittiesPallet::<T>::start_auction(
+            RawOrigin::Signed(seller).into(),
+            kitty_id,
+            100u32.into(),
+            10u32.into(),
+        )?;
+        KittiesPallet::<T>::bid(RawOrigin::Signed(bidder.clone()).into(), kitty_id, 100u32.into())?;
+        frame_system::Pallet::<T>::set_block_number(
+            frame_system::Pallet::<T>::block_number() + 10u32.into(),
+        );
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(bidder));
+    }
+
+    approve {
+        let caller: T::AccountId = whitelisted_caller();
+        let operator: T::AccountId = account("operator", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+    }: _(RawOrigin::Signed(caller), operator.clone(), kitty_id)
+    verify {
+        assert_eq!(Approvals::<T>::get(kitty_id), Some(operator));
+    }
+
+    transfer_from {
+        let caller: T::AccountId = whitelisted_caller();
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&owner, [0u8; 16]);
+        Approvals::<T>::insert(kitty_id, &caller);
+    }: _(RawOrigin::Signed(caller), owner, recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(recipient));
+    }
+
+    approve_swap {
+        let caller: T::AccountId = whitelisted_caller();
+        let counterparty: T::AccountId = account("counterparty", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let their_kitty_id = insert_kitty::<T>(&counterparty, [1u8; 16]);
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, counterparty.clone(), their_kitty_id)
+    verify {
+        assert_eq!(SwapApprovals::<T>::get(&caller, kitty_id), Some((counterparty, their_kitty_id)));
+    }
+
+    swap {
+        let caller: T::AccountId = whitelisted_caller();
+        let counterparty: T::AccountId = account("counterparty", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&caller, [0u8; 16]);
+        let their_kitty_id = insert_kitty::<T>(&counterparty, [1u8; 16]);
+        SwapApprovals::<T>::insert(&counterparty, their_kitty_id, (caller.clone(), kitty_id));
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, counterparty.clone(), their_kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(counterparty));
+        assert_eq!(OwnerOf::<T>::get(their_kitty_id), Some(caller));
+    }
+
+    set_paused {
+        let origin = T::PauseOrigin::successful_origin();
+    }: _<T::Origin>(origin, true)
+    verify {
+        assert!(Paused::<T>::get());
+    }
+
+    set_breeding_paused {
+        let origin = T::PauseOrigin::successful_origin();
+    }: _<T::Origin>(origin, true)
+    verify {
+        assert!(BreedingPaused::<T>::get());
+    }
+
+    admin_set_gender {
+        let owner: T::AccountId = account("owner", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&owner, [0u8; 16]);
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, kitty_id, KittyGender::Female)
+    verify {
+        assert_eq!(GenderOverride::<T>::get(kitty_id), Some(KittyGender::Female));
+    }
+
+    force_transfer {
+        let from: T::AccountId = account("from", 0, SEED);
+        let to: T::AccountId = account("to", 0, SEED);
+        let kitty_id = insert_kitty::<T>(&from, [0u8; 16]);
+        LockedKitties::<T>::insert(kitty_id, ());
+        let origin = T::ForceOrigin::successful_origin();
+    }: _<T::Origin>(origin, from, to.clone(), kitty_id)
+    verify {
+        assert_eq!(OwnerOf::<T>::get(kitty_id), Some(to));
+    }
+}
+
+impl_benchmark_test_suite!(
+    KittiesPallet,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
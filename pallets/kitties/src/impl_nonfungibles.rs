@@ -0,0 +1,84 @@
+//! Implementations of the `nonfungibles` traits for kitties.
+
+use super::*;
+use frame_support::traits::tokens::nonfungibles::{Inspect, Mutate, Transfer};
+
+/// The attribute key under which the DNA of a kitty is exposed.
+pub const DNA_ATTRIBUTE: &[u8] = b"dna";
+
+impl<T: Config> Inspect<<T as frame_system::Config>::AccountId> for Pallet<T> {
+    type InstanceId = u32;
+    type ClassId = ();
+
+    fn owner(_class: &Self::ClassId, instance: &Self::InstanceId) -> Option<T::AccountId> {
+        Self::owner_of(instance)
+    }
+
+    fn attribute(
+        _class: &Self::ClassId,
+        instance: &Self::InstanceId,
+        key: &[u8],
+    ) -> Option<Vec<u8>> {
+        if key == DNA_ATTRIBUTE {
+            Self::kitties(instance).map(|kitty| kitty.as_bytes().to_vec())
+        } else {
+            let key: BoundedVec<u8, T::MaxKeyLen> = key.to_vec().try_into().ok()?;
+            Self::kitty_attributes(instance, key).map(|value| value.to_vec())
+        }
+    }
+}
+
+impl<T: Config> Transfer<<T as frame_system::Config>::AccountId> for Pallet<T> {
+    fn transfer(
+        _class: &Self::ClassId,
+        instance: &Self::InstanceId,
+        destination: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_not_paused()?;
+
+        let owner = Self::owner_of(instance).ok_or(Error::<T>::InvalidKittyId)?;
+        ensure!(&owner != destination, Error::<T>::TransferToSelf);
+
+        Self::do_transfer(&owner, destination, *instance)
+    }
+}
+
+impl<T: Config> Mutate<<T as frame_system::Config>::AccountId> for Pallet<T> {
+    fn mint_into(
+        _class: &Self::ClassId,
+        instance: &Self::InstanceId,
+        who: &T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_not_paused()?;
+
+        let kitty_id = *instance;
+        ensure!(
+            !Kitties::<T>::contains_key(kitty_id)
+                && !AbandonedKitties::<T>::contains_key(kitty_id)
+                && !DeadKitties::<T>::contains_key(kitty_id),
+            Error::<T>::KittyAlreadyExists
+        );
+
+        ensure!(kitty_id < T::MaxSupply::get(), Error::<T>::MaxSupplyReached);
+
+        let next_id = kitty_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+
+        Self::create_random_at(who, Some(kitty_id), who)?;
+
+        // Keep `NextKittyId` ahead of every id in use so `create` never hands it out again
+        if next_id > Self::next_kitty_id() {
+            NextKittyId::<T>::put(next_id);
+            Self::check_id_threshold(next_id);
+        }
+
+        Ok(())
+    }
+
+    fn burn_from(_class: &Self::ClassId, instance: &Self::InstanceId) -> DispatchResult {
+        Self::ensure_not_paused()?;
+
+        let owner = Self::owner_of(instance).ok_or(Error::<T>::InvalidKittyId)?;
+
+        Self::do_burn(&owner, *instance)
+    }
+}
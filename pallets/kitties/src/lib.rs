@@ -1,160 +1,3108 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::Randomness};
+use frame_support::{
+    dispatch::DispatchResult,
+    pallet_prelude::*,
+    traits::{
+        BalanceStatus, Currency, ExistenceRequirement, Imbalance, Randomness, ReservableCurrency,
+        StorageVersion, WithdrawReasons,
+    },
+    transactional,
+    weights::WithPostDispatchInfo,
+};
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
-use sp_io::hashing::blake2_128;
-use sp_runtime::ArithmeticError;
+use sp_io::hashing::{blake2_128, blake2_256};
+use sp_runtime::{offchain::storage::StorageValueRef, traits::Hash, ArithmeticError, Permill};
+use sp_std::{convert::TryInto, prelude::*};
 
-#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+mod impl_nonfungibles;
+pub mod migrations;
+pub mod runtime_api;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+/// Storage key of the litter size forced by benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+const FORCED_LITTER_SIZE: &[u8] = b":kitties:forced_litter_size";
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum KittyGender {
     Male,
     Female,
 }
 
+impl KittyGender {
+    pub fn from_dna(dna: &[u8; 16]) -> Self {
+        // first bit of the random byte
+        if dna[0] % 2 == 0 {
+            KittyGender::Male
+        } else {
+            KittyGender::Female
+        }
+    }
+
+    /// The gender a kitty of this gender can breed with.
+    pub fn opposite(&self) -> KittyGender {
+        match self {
+            KittyGender::Male => KittyGender::Female,
+            KittyGender::Female => KittyGender::Male,
+        }
+    }
+}
+
 // Struct for holding Kitty information.
 // encode and decode: transform into binary data
 // RuntimeDebug: allow to print the format of the kitty struct
 // PartialEq to compare Kitty
+// The DNA is exactly one blake2_128 output. Its length is deliberately fixed: genders, traits,
+// breeding and the storage layout all index into these 16 bytes.
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
 pub struct Kitty(pub [u8; 16]);
 
+/// Body color of a kitty, decoded from DNA byte 1.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum BodyColor {
+    Black,
+    White,
+    Ginger,
+    Grey,
+    Brown,
+    Cream,
+    Blue,
+    Lilac,
+}
+
+impl BodyColor {
+    fn from_byte(byte: u8) -> Self {
+        match byte % 8 {
+            0 => BodyColor::Black,
+            1 => BodyColor::White,
+            2 => BodyColor::Ginger,
+            3 => BodyColor::Grey,
+            4 => BodyColor::Brown,
+            5 => BodyColor::Cream,
+            6 => BodyColor::Blue,
+            _ => BodyColor::Lilac,
+        }
+    }
+}
+
+/// Eye shape of a kitty, decoded from DNA byte 2.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum EyeShape {
+    Round,
+    Almond,
+    Slit,
+    Sleepy,
+}
+
+impl EyeShape {
+    fn from_byte(byte: u8) -> Self {
+        match byte % 4 {
+            0 => EyeShape::Round,
+            1 => EyeShape::Almond,
+            2 => EyeShape::Slit,
+            _ => EyeShape::Sleepy,
+        }
+    }
+}
+
+/// Coat pattern of a kitty, decoded from DNA byte 3.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum Pattern {
+    Solid,
+    Tabby,
+    Spotted,
+    Tortoiseshell,
+}
+
+impl Pattern {
+    fn from_byte(byte: u8) -> Self {
+        match byte % 4 {
+            0 => Pattern::Solid,
+            1 => Pattern::Tabby,
+            2 => Pattern::Spotted,
+            _ => Pattern::Tortoiseshell,
+        }
+    }
+}
+
+/// The visual traits of a kitty, derived from its DNA.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub struct KittyTraits {
+    pub body_color: BodyColor,
+    pub eye_shape: EyeShape,
+    pub pattern: Pattern,
+}
+
 impl Kitty {
+    /// A kitty with the given DNA.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Kitty(bytes)
+    }
+
+    /// The DNA of the kitty.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    #[inline]
     pub fn gender(&self) -> KittyGender {
-        // first bit of the random byte
-        if self.0[0] % 2 == 0 {
-            KittyGender::Male
-        } else {
-            KittyGender::Female
+        KittyGender::from_dna(&self.0)
+    }
+
+    #[inline]
+    pub fn is_male(&self) -> bool {
+        self.gender() == KittyGender::Male
+    }
+
+    #[inline]
+    pub fn is_female(&self) -> bool {
+        self.gender() == KittyGender::Female
+    }
+
+    /// Color index between 0 and 7, the position of `traits().body_color` in `BodyColor`.
+    #[inline]
+    pub fn color(&self) -> u8 {
+        self.0[1] % 8
+    }
+
+    pub fn traits(&self) -> KittyTraits {
+        KittyTraits {
+            body_color: BodyColor::from_byte(self.0[1]),
+            eye_shape: EyeShape::from_byte(self.0[2]),
+            pattern: Pattern::from_byte(self.0[3]),
+        }
+    }
+
+    /// Rarity of the kitty, between 0 and 32.
+    ///
+    /// Every DNA byte in the rare range (`RARE_BYTE_THRESHOLD` and up) scores one point and a
+    /// byte of `0xff` scores two. Higher is rarer.
+    pub fn rarity_score(&self) -> u32 {
+        self.0
+            .iter()
+            .map(|byte| match *byte {
+                0xff => 2,
+                b if b >= RARE_BYTE_THRESHOLD => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Name of the rarity band the rarity score falls in: no rare byte at all is common, up to a
+    /// quarter of the maximum score uncommon, up to half rare and anything above legendary.
+    pub fn rarity_tier(&self) -> &'static str {
+        match self.rarity_score() {
+            0 => "Common",
+            1..=8 => "Uncommon",
+            9..=16 => "Rare",
+            _ => "Legendary",
+        }
+    }
+}
+
+/// Number of DNA bits that differ between `a` and `b`, between 0 and 128.
+///
+/// Close relatives share most of their DNA, so a low distance hints at inbreeding.
+pub fn dna_distance(a: &Kitty, b: &Kitty) -> u32 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum()
+}
+
+/// Move every byte of `dna` into the rare range, keeping its high nibble as the low one.
+fn boost_rarity(dna: &mut [u8; 16]) {
+    for byte in dna.iter_mut() {
+        *byte = RARE_BYTE_THRESHOLD | (*byte >> 4);
+    }
+}
+
+/// Append the decimal digits of `value` to `out`.
+fn push_decimal(out: &mut Vec<u8>, value: u32) {
+    let mut digits = [0u8; 10];
+    let mut len = 0;
+    let mut rest = value;
+    loop {
+        digits[len] = b'0' + (rest % 10) as u8;
+        len += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    out.extend(digits[..len].iter().rev());
+}
+
+/// DNA bytes at or above this value count as rare.
+pub const RARE_BYTE_THRESHOLD: u8 = 0xf0;
+
+/// Hook for other pallets to react to newly minted kitties.
+pub trait OnKittyCreated<AccountId> {
+    /// Called once `kitty_id` has been created for `owner`.
+    fn on_created(owner: &AccountId, kitty_id: u32);
+}
+
+impl<AccountId> OnKittyCreated<AccountId> for () {
+    fn on_created(_owner: &AccountId, _kitty_id: u32) {}
+}
+
+/// Strategy combining the DNA of two parents into the DNA of their offspring.
+pub trait DnaMixer {
+    /// Mix `parent1` and `parent2`, using `selector` as the source of randomness.
+    fn mix(parent1: &[u8; 16], parent2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16];
+}
+
+/// Takes every bit set in the selector from the first parent and all other bits from the second.
+pub struct BitmaskMixer;
+
+impl DnaMixer for BitmaskMixer {
+    fn mix(parent1: &[u8; 16], parent2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+        let mut new_dna = [0u8; 16];
+
+        // Combine parents and selector to create new kitty
+        for (i, byte) in new_dna.iter_mut().enumerate() {
+            // bitwise OR is "|" (Option - 7)
+            *byte = (selector[i] & parent1[i]) | (!selector[i] & parent2[i]);
         }
+
+        new_dna
     }
 }
 
+/// A kitty bundled with everything the pallet knows about it.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct KittyInfo<BlockNumber> {
+    pub dna: [u8; 16],
+    pub gender: KittyGender,
+    pub generation: u32,
+    pub birth_block: Option<BlockNumber>,
+    pub parents: Option<(u32, u32)>,
+}
+
+/// An English auction of a kitty.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    /// The minimum bid until the first bid arrives, the highest bid afterwards.
+    pub highest_bid: Balance,
+    pub highest_bidder: Option<AccountId>,
+    pub end_block: BlockNumber,
+}
+
+/// Aggregate figures over all living kitties.
+#[derive(Clone, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct RarityStats {
+    pub count: u64,
+    pub males: u64,
+    pub females: u64,
+    /// Sum of the rarity scores of all kitties.
+    pub total_rarity: u64,
+    pub max_rarity: u32,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 
     use super::*;
 
     #[pallet::config]
-    pub trait Config: frame_system::Config + pallet_randomness_collective_flip::Config {
+    pub trait Config: frame_system::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The source of randomness used to generate kitty DNA.
+        type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+
+        /// The maximum number of items handled by a single batch call.
+        type MaxBatchSize: Get<u32>;
+
+        /// The number of blocks a burned kitty stays in the graveyard before it may be cleaned up.
+        #[pallet::constant]
+        type GraveyardRetention: Get<Self::BlockNumber>;
+
+        /// The maximum length of a kitty name in bytes.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+
+        /// The maximum length of an attribute key in bytes.
+        #[pallet::constant]
+        type MaxKeyLen: Get<u32>;
+
+        /// The maximum length of an attribute value in bytes.
+        #[pallet::constant]
+        type MaxValueLen: Get<u32>;
+
+        /// The maximum number of attributes a single kitty can carry.
+        #[pallet::constant]
+        type MaxAttributes: Get<u32>;
+
+        /// The maximum number of kitties a single account can own.
+        #[pallet::constant]
+        type MaxKittiesOwned: Get<u32>;
+
+        /// The currency used to trade kitties and to reserve kitty deposits.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// The amount reserved from the owner for every kitty minted.
+        #[pallet::constant]
+        type KittyDeposit: Get<BalanceOf<Self>>;
+
+        /// The amount reserved from an owner for every kitty they reserve for breeding.
+        #[pallet::constant]
+        type BreedingDeposit: Get<BalanceOf<Self>>;
+
+        /// The free balance an account needs to create kitties. Nothing of it is reserved.
+        #[pallet::constant]
+        type MinCreatorBalance: Get<BalanceOf<Self>>;
+
+        /// The first block kitties can be created or bred at. Collective flip randomness needs
+        /// 81 blocks to become unpredictable, a chain with a proper randomness source can use 0.
+        #[pallet::constant]
+        type MinBlockForCreation: Get<Self::BlockNumber>;
+
+        /// Once `NextKittyId` reaches this value a warning event is emitted.
+        type IdWarningThreshold: Get<u32>;
+
+        /// The number of blocks a kitty has to wait after breeding before it can breed again.
+        #[pallet::constant]
+        type BreedCooldownPeriod: Get<Self::BlockNumber>;
+
+        /// The origin allowed to override kitty genders.
+        type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+        /// How the DNA of two parents is combined when breeding.
+        type DnaMixer: DnaMixer;
+
+        /// The chance, out of 256, that a single offspring DNA byte is replaced by a random one.
+        type MutationRate: Get<u8>;
+
+        /// Called whenever a kitty is created or bred.
+        type OnKittyCreated: OnKittyCreated<Self::AccountId>;
+
+        /// The origin allowed to pause and resume breeding.
+        type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The origin allowed to move kitties between accounts without the owner's consent.
+        type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The number of past owners remembered per kitty.
+        type MaxHistory: Get<u32>;
+
+        /// The maximum number of kitty ids ever handed out, `u32::MAX` for no limit.
+        type MaxSupply: Get<u32>;
+
+        /// The number of blocks between two rarity reports of the off-chain worker.
+        type OcwInterval: Get<u32>;
+
+        /// The origin allowed to create kitties for other accounts.
+        type GiftOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+        /// The maximum number of offspring a single breeding can produce.
+        type MaxLitterSize: Get<u32>;
+
+        /// Whether kitties of the same color are kept from breeding with each other.
+        #[pallet::constant]
+        type ForbidSameColorBreeding: Get<bool>;
+
+        /// The number of blocks a kitty must live before it can breed.
+        #[pallet::constant]
+        type MaturityPeriod: Get<Self::BlockNumber>;
+
+        /// The number of times a single kitty can breed.
+        #[pallet::constant]
+        type MaxBreedings: Get<u32>;
+
+        /// The lowest generation a kitty must have to breed, zero lets every kitty breed.
+        #[pallet::constant]
+        type MinBreedingGeneration: Get<u32>;
+
+        /// The share of every sale price that goes to `FeeCollector`.
+        type MarketplaceFee: Get<Permill>;
+
+        /// The account receiving marketplace and rename fees and the unburned breeding fees.
+        type FeeCollector: Get<Self::AccountId>;
+
+        /// Whether no two kitties may carry the same name.
+        ///
+        /// `NameOwner` is only kept while this is set, turning it on for a live chain needs a
+        /// migration filling it.
+        #[pallet::constant]
+        type UniqueNames: Get<bool>;
+
+        /// The fee paid to `FeeCollector` for every rename, zero makes renaming free.
+        #[pallet::constant]
+        type RenameFee: Get<BalanceOf<Self>>;
+
+        /// The fee paid for every breeding, zero makes breeding free.
+        #[pallet::constant]
+        type BreedFee: Get<BalanceOf<Self>>;
+
+        /// The share of `BreedFee` that is burned, the rest goes to `FeeCollector`.
+        #[pallet::constant]
+        type BurnRatio: Get<Permill>;
+
+        /// The maximum number of listings that can expire in the same block.
+        #[pallet::constant]
+        type MaxExpiringPerBlock: Get<u32>;
     }
 
+    /// Off-chain storage key of the block the last rarity report was logged at.
+    pub const OCW_LAST_REPORT_KEY: &[u8] = b"pallet-kitties::last-rarity-report";
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     // blake2 128 bit secure hasher is the default to keep it simple
-    /// Stores all the kitties. Key is (user, kitty_id).
+    /// Stores all the kitties. Key is kitty_id.
     #[pallet::storage]
     #[pallet::getter(fn kitties)]
-    pub type Kitties<T: Config> = StorageDoubleMap<
+    pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, u32, Kitty, OptionQuery>;
+
+    /// Stores the owner of each kitty. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn owner_of)]
+    pub type OwnerOf<T: Config> = StorageMap<_, Blake2_128Concat, u32, T::AccountId, OptionQuery>;
+
+    /// Stores the next kitty Id.
+    #[pallet::storage]
+    #[pallet::getter(fn next_kitty_id)]
+    pub type NextKittyId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Whether the `KittyIdThresholdReached` warning has already been emitted.
+    #[pallet::storage]
+    #[pallet::getter(fn id_threshold_reached)]
+    pub type IdThresholdReached<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Stores the optional name of each kitty. Key is (user, kitty_id).
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_names)]
+    pub type KittyNames<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
         T::AccountId,
         Blake2_128Concat,
         u32,
-        Kitty,
+        BoundedVec<u8, T::MaxNameLength>,
         OptionQuery,
     >;
 
-    /// Stores the next kitty Id.
+    /// Stores the kitty carrying each name, only kept when `UniqueNames` is set.
     #[pallet::storage]
-    #[pallet::getter(fn next_kitty_id)]
-    pub type NextKittyId<T: Config> = StorageValue<_, u32, ValueQuery>;
+    #[pallet::getter(fn name_owner)]
+    pub type NameOwner<T: Config> =
+        StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxNameLength>, u32, OptionQuery>;
 
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    #[pallet::metadata(T::AccountId = "AccountId")]
-    pub enum Event<T: Config> {
-        /// A kitty is created. \[owner, kitty_id, kitty\]
-        KittyCreated(T::AccountId, u32, Kitty),
-        KittyBred(T::AccountId, u32, Kitty),
-    }
+    /// Stores the free-form attributes of each kitty. Key is (kitty_id, attribute key).
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_attributes)]
+    pub type KittyAttributes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        Blake2_128Concat,
+        BoundedVec<u8, T::MaxKeyLen>,
+        BoundedVec<u8, T::MaxValueLen>,
+        OptionQuery,
+    >;
 
-    #[pallet::pallet]
-    #[pallet::generate_store(pub(super) trait Store)]
-    pub struct Pallet<T>(_);
+    /// Stores the number of attributes of each kitty. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn attribute_count)]
+    pub type AttributeCount<T: Config> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
 
-    /// Error for the kitties pallet.
-    #[pallet::error]
-    pub enum Error<T> {
-        SameGender,
-        InvalidKittyId,
-    }
+    /// Stores the operator approved to transfer each kitty on behalf of its owner.
+    #[pallet::storage]
+    #[pallet::getter(fn approvals)]
+    pub type Approvals<T: Config> = StorageMap<_, Blake2_128Concat, u32, T::AccountId, OptionQuery>;
 
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
-        /// Create a new kitty
-        #[pallet::weight(1000)]
-        pub fn create(origin: OriginFor<T>) -> DispatchResult {
-            let sender = ensure_signed(origin)?;
+    /// Stores the account allowed to breed with each kitty. Key is (owner, kitty_id).
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_approvals)]
+    pub type BreedingApprovals<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        T::AccountId,
+        OptionQuery,
+    >;
 
-            NextKittyId::<T>::try_mutate(|next_id| -> DispatchResult {
-                let current_id = *next_id;
-                *next_id = next_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+    /// Stores the kitty each reserved kitty may only breed with and the last block of the
+    /// reservation. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_locks)]
+    pub type BreedingLocks<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, (u32, T::BlockNumber), OptionQuery>;
 
-                // Generate a random 128bit value
-                let payload = (
-                    <pallet_randomness_collective_flip::Pallet<T> as Randomness<
-                        T::Hash,
-                        T::BlockNumber,
-                    >>::random_seed()
-                    .0,
-                    &sender,
-                    <frame_system::Pallet<T>>::extrinsic_index(),
-                );
-                let dna = payload.using_encoded(blake2_128);
+    /// Stores the account and amount of the deposit taken for each breeding reservation.
+    /// Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_deposits)]
+    pub type BreedingDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+    /// Stores the swap each kitty's owner agreed to, as the counterparty and the kitty they give
+    /// in exchange. Key is (owner, kitty_id).
+    #[pallet::storage]
+    #[pallet::getter(fn swap_approvals)]
+    pub type SwapApprovals<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        (T::AccountId, u32),
+        OptionQuery,
+    >;
+
+    /// Stores the sale price of each listed kitty. Key is (user, kitty_id).
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_prices)]
+    pub type KittyPrices<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        BalanceOf<T>,
+        OptionQuery,
+    >;
 
-                // Create and store kitty
-                let kitty = Kitty(dna);
-                Kitties::<T>::insert(&sender, current_id, &kitty);
+    /// Stores the deposit reserved for each kitty. Value is (depositor, amount).
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_deposits)]
+    pub type KittyDeposits<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, (T::AccountId, BalanceOf<T>), OptionQuery>;
 
-                // Emit event
-                Self::deposit_event(Event::KittyCreated(sender, current_id, kitty));
+    /// Stores the parents of each bred kitty. Key is kitty_id, value is (kitty_id_1, kitty_id_2).
+    #[pallet::storage]
+    #[pallet::getter(fn parents_of)]
+    pub type KittyParents<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, (u32, u32), OptionQuery>;
 
-                Ok(())
-            })
-        }
+    /// Stores the generation of each kitty. Created kitties are generation 0.
+    #[pallet::storage]
+    #[pallet::getter(fn generation_of)]
+    pub type KittyGeneration<T: Config> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
 
-        /// Breed kitties
-        #[pallet::weight(1000)]
-        pub fn breed(origin: OriginFor<T>, kitty_id_1: u32, kitty_id_2: u32) -> DispatchResult {
-            let sender = ensure_signed(origin)?;
-            let kitty1 = Self::kitties(&sender, kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-            let kitty2 = Self::kitties(&sender, kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+    /// Stores the block number each kitty was born in.
+    #[pallet::storage]
+    #[pallet::getter(fn birth_block)]
+    pub type KittyBirthBlock<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, T::BlockNumber, OptionQuery>;
 
-            ensure!(kitty1.gender() != kitty2.gender(), Error::<T>::SameGender);
+    /// Stores the earliest block each kitty may breed again.
+    #[pallet::storage]
+    #[pallet::getter(fn cooldown_of)]
+    pub type KittyCooldown<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, T::BlockNumber, OptionQuery>;
 
-            NextKittyId::<T>::try_mutate(|next_id| -> DispatchResult {
-                let kitty_id = *next_id;
-                *next_id = next_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+    /// Stores how many times each kitty has bred.
+    #[pallet::storage]
+    #[pallet::getter(fn breed_count)]
+    pub type BreedCount<T: Config> = StorageMap<_, Blake2_128Concat, u32, u32, ValueQuery>;
 
-                let kitty1_dna = kitty1.0;
-                let kitty2_dna = kitty2.0;
+    /// Genders set by the admin, taking precedence over the DNA. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn gender_override)]
+    pub type GenderOverride<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, KittyGender, OptionQuery>;
 
-                let payload = (
-                    <pallet_randomness_collective_flip::Pallet<T> as Randomness<
-                        T::Hash,
-                        T::BlockNumber,
-                    >>::random_seed()
-                    .0,
-                    &sender,
-                    <frame_system::Pallet<T>>::extrinsic_index(),
-                );
-                let selector = payload.using_encoded(blake2_128);
+    /// Kitties that can't be transferred, burned or bred. Key is kitty_id.
+    #[pallet::storage]
+    pub type LockedKitties<T: Config> = StorageMap<_, Blake2_128Concat, u32, (), OptionQuery>;
 
-                let mut new_dna = [0u8; 16];
+    /// Kitties that can never breed again. Key is kitty_id.
+    #[pallet::storage]
+    pub type SterileKitties<T: Config> = StorageMap<_, Blake2_128Concat, u32, (), OptionQuery>;
 
-                // Combine parents and selector to create new kitty
-                for i in 0..kitty1_dna.len() {
-                    // bitwise OR is "|" (Option - 7)
-                    new_dna[i] = (selector[i] & kitty1_dna[i]) | (!selector[i] & kitty2_dna[i]);
-                }
+    /// The last owner and the block of death of every burned kitty. Key is kitty_id.
+    #[pallet::storage]
+    pub type DeadKitties<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, (T::AccountId, T::BlockNumber), OptionQuery>;
 
-                let new_kitty = Kitty(new_dna);
+    /// Stores the burned kitty ids in the order they died, keyed by queue position.
+    #[pallet::storage]
+    pub type GraveyardQueue<T: Config> = StorageMap<_, Twox64Concat, u64, u32, OptionQuery>;
 
-                Kitties::<T>::insert(&sender, kitty_id, &new_kitty);
+    /// Stores the first used and the next free position of `GraveyardQueue`.
+    #[pallet::storage]
+    #[pallet::getter(fn graveyard_range)]
+    pub type GraveyardRange<T: Config> = StorageValue<_, (u64, u64), ValueQuery>;
 
-                Self::deposit_event(Event::KittyBred(sender, kitty_id, new_kitty));
-                Ok(())
-            })
+    /// Kitties given up by their owner and free to be claimed. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn abandoned)]
+    pub type AbandonedKitties<T: Config> = StorageMap<_, Blake2_128Concat, u32, Kitty, OptionQuery>;
+
+    /// Stores the number of kitties owned by each account.
+    #[pallet::storage]
+    #[pallet::getter(fn owned_count)]
+    pub type OwnedCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Stores the next per-owner index handed out to each account.
+    #[pallet::storage]
+    #[pallet::getter(fn owner_next_index)]
+    pub type OwnerNextIndex<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Stores the position of each kitty among the kitties its owner ever received.
+    /// Key is (user, kitty_id).
+    #[pallet::storage]
+    pub type OwnerIndex<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        u32,
+        OptionQuery,
+    >;
+
+    /// Stores the ids of the kitties owned by each account.
+    #[pallet::storage]
+    #[pallet::getter(fn owned)]
+    pub type KittiesOwned<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<u32, T::MaxKittiesOwned>,
+        ValueQuery,
+    >;
+
+    /// Stores the number of owned kitties. Abandoned kitties are not counted.
+    #[pallet::storage]
+    #[pallet::getter(fn kitty_count)]
+    pub type KittyCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Stores the number of owned male kitties.
+    #[pallet::storage]
+    #[pallet::getter(fn male_count)]
+    pub type MaleCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Stores the number of owned female kitties.
+    #[pallet::storage]
+    #[pallet::getter(fn female_count)]
+    pub type FemaleCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Stores the number of kitties ever minted with `create`.
+    #[pallet::storage]
+    #[pallet::getter(fn total_created)]
+    pub type TotalCreated<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Stores the number of kitties ever born from breeding.
+    #[pallet::storage]
+    #[pallet::getter(fn total_bred)]
+    pub type TotalBred<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Stores the id of the kitty most recently minted for each account.
+    #[pallet::storage]
+    #[pallet::getter(fn last_created_kitty)]
+    pub type LastCreatedKitty<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, OptionQuery>;
+
+    /// Stores how many kitties have been minted for each account, mixed into their DNA.
+    #[pallet::storage]
+    #[pallet::getter(fn account_nonce)]
+    pub type AccountNonce<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Stores the owners of each kitty, oldest first. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn history_of)]
+    pub type KittyHistory<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, BoundedVec<T::AccountId, T::MaxHistory>, ValueQuery>;
+
+    /// Stores the block each expiring listing is removed at. Key is (owner, kitty_id).
+    #[pallet::storage]
+    #[pallet::getter(fn listing_expiry)]
+    pub type ListingExpiry<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        u32,
+        T::BlockNumber,
+        OptionQuery,
+    >;
+
+    /// Stores the listings to expire at each block, in the order they were listed.
+    ///
+    /// Entries whose listing has been changed since are skipped when the block is reached.
+    #[pallet::storage]
+    #[pallet::getter(fn expiring_listings)]
+    pub type ExpiringListings<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<(T::AccountId, u32), T::MaxExpiringPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Stores the running auction of each kitty. Key is kitty_id.
+    #[pallet::storage]
+    #[pallet::getter(fn auctions)]
+    pub type KittyAuctions<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        Auction<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Stores the DNA of every living kitty, keeping each DNA unique.
+    #[pallet::storage]
+    pub type DnaExists<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 16], (), OptionQuery>;
+
+    /// Stores the DNA of the kitties each account owns. Key is (user, dna).
+    #[pallet::storage]
+    pub type OwnerDna<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        [u8; 16],
+        (),
+        OptionQuery,
+    >;
+
+    /// Whether breeding is currently paused.
+    #[pallet::storage]
+    #[pallet::getter(fn breeding_paused)]
+    pub type BreedingPaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Whether every extrinsic of the pallet is currently paused.
+    #[pallet::storage]
+    #[pallet::getter(fn paused)]
+    pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Kitties that exist from block zero, as (owner, dna).
+        pub kitties: Vec<(T::AccountId, [u8; 16])>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                kitties: Vec::new(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for (index, (owner, dna)) in self.kitties.iter().enumerate() {
+                assert!(
+                    !DnaExists::<T>::contains_key(dna),
+                    "duplicate kitty DNA in genesis config"
+                );
+
+                let kitty_id = index as u32;
+                let kitty = Kitty::from_bytes(*dna);
+                Pallet::<T>::index_kitty(owner, kitty_id, &kitty)
+                    .expect("genesis kitties must respect MaxKittiesOwned");
+
+                Kitties::<T>::insert(kitty_id, kitty);
+                OwnerOf::<T>::insert(kitty_id, owner);
+                KittyBirthBlock::<T>::insert(kitty_id, T::BlockNumber::from(0u32));
+            }
+
+            NextKittyId::<T>::put(self.kitties.len() as u32);
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    #[pallet::metadata(
+        T::AccountId = "AccountId",
+        BalanceOf<T> = "Balance",
+        T::BlockNumber = "BlockNumber"
+    )]
+    pub enum Event<T: Config> {
+        /// A kitty is created. \[owner, kitty_id, kitty, gender\]
+        ///
+        /// Topics: the hashes of `kitty_id` and `owner`.
+        KittyCreated(T::AccountId, u32, Kitty, KittyGender),
+        /// A kitty is bred. \[owner, kitty_id, kitty, gender, generation\]
+        ///
+        /// Topics: the hashes of `kitty_id` and `owner`.
+        KittyBred(T::AccountId, u32, Kitty, KittyGender, u32),
+        /// A kitty is transferred. \[from, to, kitty_id\]
+        ///
+        /// Topics: the hashes of `kitty_id`, `from` and `to`.
+        KittyTransferred(T::AccountId, T::AccountId, u32),
+        /// A kitty is burned. \[owner, kitty_id\]
+        KittyBurned(T::AccountId, u32),
+        /// A kitty is renamed. \[owner, kitty_id, name, fee\]
+        KittyRenamed(T::AccountId, u32, Vec<u8>, BalanceOf<T>),
+        /// The price of a kitty is updated. \[owner, kitty_id, price\]
+        KittyPriceUpdated(T::AccountId, u32, Option<BalanceOf<T>>),
+        /// A kitty is sold, the fee is part of the price. \[from, to, kitty_id, price, fee\]
+        KittySold(T::AccountId, T::AccountId, u32, BalanceOf<T>, BalanceOf<T>),
+        /// An operator is approved to transfer a kitty. \[owner, operator, kitty_id\]
+        Approval(T::AccountId, T::AccountId, u32),
+        /// The next kitty id reached the configured warning threshold. \[next_kitty_id\]
+        KittyIdThresholdReached(u32),
+        /// The gender of a kitty was overridden by the admin. \[kitty_id, gender\]
+        GenderOverridden(u32, KittyGender),
+        /// An owner allowed a partner to breed with their kitty. \[owner, partner, kitty_id\]
+        BreedingApproved(T::AccountId, T::AccountId, u32),
+        /// A kitty is bred from parents of two owners. \[owner, partner, kitty_id\]
+        CoBred(T::AccountId, T::AccountId, u32),
+        /// Bytes of a bred kitty's DNA mutated. \[kitty_id, mutated_bytes\]
+        KittyMutated(u32, u8),
+        /// Breeding was paused or resumed. \[paused\]
+        BreedingPauseToggled(bool),
+        /// All kitties of an account are transferred. \[from, to, count\]
+        AllKittiesTransferred(T::AccountId, T::AccountId, u32),
+        /// A kitty is put up for auction. \[seller, kitty_id, min_bid, end_block\]
+        AuctionStarted(T::AccountId, u32, BalanceOf<T>, T::BlockNumber),
+        /// A new highest bid is placed. \[bidder, kitty_id, amount\]
+        BidPlaced(T::AccountId, u32, BalanceOf<T>),
+        /// An auction is settled. \[kitty_id, winner, amount\]
+        AuctionClosed(u32, Option<T::AccountId>, BalanceOf<T>),
+        /// An auction is called off before its end, refunding the highest bid.
+        /// \[kitty_id, refunded_bidder, amount\]
+        AuctionCancelled(u32, Option<T::AccountId>, BalanceOf<T>),
+        /// A kitty is made sterile. \[kitty_id\]
+        KittySterilized(u32),
+        /// A kitty is created for another account. \[from, to, kitty_id\]
+        KittyGifted(T::AccountId, T::AccountId, u32),
+        /// An owner gave up a kitty. \[owner, kitty_id\]
+        KittyAbandoned(T::AccountId, u32),
+        /// An abandoned kitty found a new owner. \[owner, kitty_id\]
+        KittyClaimed(T::AccountId, u32),
+        /// More than one offspring was bred at once, fewer are born than the litter size when
+        /// the owner can't hold them all. \[parent1, parent2, born, litter_size\]
+        LitterBorn(u32, u32, u32, u32),
+        /// A burned kitty is recorded in the graveyard. \[kitty_id, owner, block\]
+        KittyBuried(u32, T::AccountId, T::BlockNumber),
+        /// An account that owned no kitties created one. \[owner, kitty_id\]
+        FirstKittyCreated(T::AccountId, u32),
+        /// Two kitties are burned to create a new one. \[parent1, parent2, kitty_id\]
+        KittiesFused(u32, u32, u32),
+        /// An owner agreed to swap their kitty for one of the counterparty.
+        /// \[owner, kitty_id, counterparty, counterparty_kitty_id\]
+        SwapApproved(T::AccountId, u32, T::AccountId, u32),
+        /// Two owners exchanged a kitty each. \[a, b, kitty_a, kitty_b\]
+        KittiesSwapped(T::AccountId, T::AccountId, u32, u32),
+        /// A listing reached its expiry block and was removed. \[kitty_id\]
+        ListingExpired(u32),
+        /// A kitty was moved by `ForceOrigin`. \[from, to, kitty_id\]
+        KittyForceTransferred(T::AccountId, T::AccountId, u32),
+        /// A kitty was reserved to breed with a kitty of another owner.
+        /// \[owner, kitty_id, partner_kitty_id, until_block\]
+        BreedingReserved(T::AccountId, u32, u32, T::BlockNumber),
+        /// The breeding deposit of an owner was slashed for breaking a reservation.
+        /// \[owner, amount\]
+        BreedingDepositSlashed(T::AccountId, BalanceOf<T>),
+        /// An attribute of a kitty was set. \[kitty_id, key, value\]
+        AttributeSet(u32, Vec<u8>, Vec<u8>),
+        /// An attribute of a kitty was removed. \[kitty_id, key\]
+        AttributeCleared(u32, Vec<u8>),
+        /// The whole pallet was paused or resumed. \[paused\]
+        PalletPauseToggled(bool),
+        /// Expired graveyard entries were removed. \[count\]
+        GraveyardCleaned(u32),
+        /// A breeding fee was paid. \[burned, to_treasury\]
+        BreedFeeCollected(BalanceOf<T>, BalanceOf<T>),
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let listings = ExpiringListings::<T>::take(now).to_vec();
+            for (owner, kitty_id) in &listings {
+                // Listings updated, delisted or sold since were queued for nothing
+                if Self::listing_expiry(owner, kitty_id) == Some(now) {
+                    ListingExpiry::<T>::remove(owner, kitty_id);
+                    KittyPrices::<T>::remove(owner, kitty_id);
+                    Self::deposit_event(Event::ListingExpired(*kitty_id));
+                }
+            }
+            T::WeightInfo::on_initialize(listings.len() as u32)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate::<T>()
+        }
+
+        fn offchain_worker(block_number: T::BlockNumber) {
+            // Remember when the stats were last logged so they aren't recomputed every block
+            let last_report = StorageValueRef::persistent(OCW_LAST_REPORT_KEY);
+            if let Ok(Some(last)) = last_report.get::<T::BlockNumber>() {
+                if block_number < last.saturating_add(T::OcwInterval::get().into()) {
+                    return;
+                }
+            }
+            last_report.set(&block_number);
+
+            let stats = Self::rarity_stats();
+            log::debug!(
+                target: "runtime::kitties",
+                "block {:?}: {} kitties, {} male, {} female, rarity total {} max {}",
+                block_number,
+                stats.count,
+                stats.males,
+                stats.females,
+                stats.total_rarity,
+                stats.max_rarity,
+            );
+        }
+    }
+
+    #[pallet::extra_constants]
+    impl<T: Config> Pallet<T> {
+        /// The maximum number of kitty ids ever handed out, `u32::MAX` for no limit.
+        fn max_supply() -> u32 {
+            T::MaxSupply::get()
+        }
+
+        /// The version of the kitties pallet crate.
+        fn version() -> Vec<u8> {
+            env!("CARGO_PKG_VERSION").as_bytes().to_vec()
+        }
+    }
+
+    /// Error for the kitties pallet.
+    #[pallet::error]
+    pub enum Error<T> {
+        SameGender,
+        InvalidKittyId,
+        TransferToSelf,
+        NameTooLong,
+        TooManyKitties,
+        NotForSale,
+        PriceTooHigh,
+        BuyFromSelf,
+        InsufficientBalance,
+        BreedWithSelf,
+        KittyOnCooldown,
+        NotApproved,
+        NotOwner,
+        BatchTooLarge,
+        KittyLocked,
+        BreedingNotApproved,
+        KittyAlreadyExists,
+        BreedingPaused,
+        DuplicateDna,
+        AuctionNotFound,
+        AuctionEnded,
+        AuctionNotEnded,
+        BidTooLow,
+        DuplicateDnaForOwner,
+        KittySterile,
+        MaxSupplyReached,
+        KittyNotAbandoned,
+        KittyDead,
+        GenerationTooLow,
+        SwapNotApproved,
+        ExpiryInPast,
+        TooManyExpiringListings,
+        KittyReservedForBreeding,
+        RandomnessNotReady,
+        RandomnessNotAvailable,
+        KeyTooLong,
+        ValueTooLong,
+        TooManyAttributes,
+        AttributeNotFound,
+        SameColor,
+        PalletPaused,
+        KittyTooYoung,
+        FertilityExhausted,
+        NameTaken,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Create a new kitty
+        #[pallet::weight(T::WeightInfo::create())]
+        #[transactional]
+        pub fn create(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::ensure_creator_balance(&sender)?;
+
+            let is_first = Self::owned_count(&sender) == 0;
+            let kitty_id = Self::create_random(&sender)?;
+            if is_first {
+                Self::deposit_event(Event::FirstKittyCreated(sender, kitty_id));
+            }
+
+            // Post dispatch info can't carry data: the id is in the event and `LastCreatedKitty`.
+            Ok(().into())
+        }
+
+        /// Create a new kitty owned by `to`, reserving its deposit from the gifter
+        #[pallet::weight(T::WeightInfo::gift_random())]
+        #[transactional]
+        pub fn gift_random(origin: OriginFor<T>, to: T::AccountId) -> DispatchResult {
+            let from = T::GiftOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let kitty_id = Self::create_random_at(&to, None, &from)?;
+
+            Self::deposit_event(Event::KittyGifted(from, to, kitty_id));
+
+            Ok(())
+        }
+
+        /// Create `count` new kitties at once
+        #[pallet::weight(T::WeightInfo::create_batch(*count))]
+        #[transactional]
+        pub fn create_batch(origin: OriginFor<T>, count: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(count <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+            Self::ensure_creator_balance(&sender)?;
+            Self::ensure_randomness_ready()?;
+
+            let payload = Self::random_payload(&sender)?;
+
+            for index in 0..count {
+                // Mix in the index so every kitty of the batch gets a distinct DNA
+                let mut payload = payload.clone();
+                index.encode_to(&mut payload);
+                let kitty = Kitty::from_bytes(blake2_128(&payload));
+                ensure!(
+                    !OwnerDna::<T>::contains_key(&sender, kitty.as_bytes()),
+                    Error::<T>::DuplicateDnaForOwner
+                );
+                let kitty_id = Self::mint(&sender, &kitty, &sender)?;
+                TotalCreated::<T>::try_mutate(|total| -> DispatchResult {
+                    *total = total.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                    Ok(())
+                })?;
+
+                let gender = kitty.gender();
+                Self::deposit_event_with_topics(
+                    Event::KittyCreated(sender.clone(), kitty_id, kitty, gender),
+                    kitty_id,
+                    &[&sender],
+                );
+
+                T::OnKittyCreated::on_created(&sender, kitty_id);
+            }
+
+            Ok(())
+        }
+
+        /// Breed kitties
+        #[pallet::weight(
+            T::WeightInfo::breed().saturating_mul(Pallet::<T>::max_litter_size() as Weight)
+        )]
+        #[transactional]
+        pub fn breed(
+            origin: OriginFor<T>,
+            kitty_id_1: u32,
+            kitty_id_2: u32,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            // Failing any of the cheap checks below only charges for the reads they made.
+            let precheck_weight = T::WeightInfo::breed_precheck();
+
+            ensure!(
+                kitty_id_1 != kitty_id_2,
+                Error::<T>::BreedWithSelf.with_weight(precheck_weight)
+            );
+
+            let kitty1 = Self::owned_kitty(&sender, kitty_id_1)
+                .map_err(|e| e.with_weight(precheck_weight))?;
+            let kitty2 = Self::owned_kitty(&sender, kitty_id_2)
+                .map_err(|e| e.with_weight(precheck_weight))?;
+
+            Self::ensure_opposite_genders((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))
+                .map_err(|e| e.with_weight(precheck_weight))?;
+
+            Self::do_breed(&sender, (kitty_id_1, kitty1), (kitty_id_2, kitty2), None)?;
+
+            // Post dispatch info can't carry data: the id is in the event and `LastCreatedKitty`.
+            Ok(().into())
+        }
+
+        /// Burn two kitties to create a single offspring with rarer DNA
+        #[pallet::weight(T::WeightInfo::fuse())]
+        #[transactional]
+        pub fn fuse(origin: OriginFor<T>, kitty_id_1: u32, kitty_id_2: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(kitty_id_1 != kitty_id_2, Error::<T>::BreedWithSelf);
+
+            let kitty1 = Self::owned_kitty(&sender, kitty_id_1)?;
+            let kitty2 = Self::owned_kitty(&sender, kitty_id_2)?;
+
+            Self::ensure_opposite_genders((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))?;
+            // Fusing is breeding too, it must not turn kitties that can't breed into a fresh one
+            Self::ensure_can_breed((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))?;
+
+            let generation = Self::generation_of(kitty_id_1)
+                .max(Self::generation_of(kitty_id_2))
+                .checked_add(1)
+                .ok_or(ArithmeticError::Overflow)?;
+
+            let selector = Self::breeding_selector(&Self::random_payload(&sender)?, 0);
+            let mut new_dna = Self::mix_parents(&kitty1, &kitty2, &selector);
+            boost_rarity(&mut new_dna);
+
+            // Burn the parents first so their slots count towards the owner's cap
+            Self::do_burn(&sender, kitty_id_1)?;
+            Self::do_burn(&sender, kitty_id_2)?;
+
+            let new_kitty = Kitty::from_bytes(new_dna);
+            let kitty_id = Self::mint(&sender, &new_kitty, &sender)?;
+            KittyParents::<T>::insert(kitty_id, (kitty_id_1, kitty_id_2));
+            KittyGeneration::<T>::insert(kitty_id, generation);
+
+            Self::deposit_event(Event::KittiesFused(kitty_id_1, kitty_id_2, kitty_id));
+
+            T::OnKittyCreated::on_created(&sender, kitty_id);
+
+            Ok(())
+        }
+
+        /// Breed several pairs of kitties at once, failing all of them if one pair fails
+        ///
+        /// The weight is benchmarked with every pair rolling a full `MaxLitterSize` litter.
+        #[pallet::weight(T::WeightInfo::breed_batch(pairs.len() as u32))]
+        #[transactional]
+        pub fn breed_batch(origin: OriginFor<T>, pairs: Vec<(u32, u32)>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(
+                pairs.len() as u32 <= T::MaxBatchSize::get(),
+                Error::<T>::BatchTooLarge
+            );
+
+            let mut ids = Vec::with_capacity(pairs.len() * 2);
+            for (kitty_id_1, kitty_id_2) in &pairs {
+                ids.push(*kitty_id_1);
+                ids.push(*kitty_id_2);
+            }
+            Self::ensure_owns_all(&sender, &ids)?;
+
+            for (index, (kitty_id_1, kitty_id_2)) in pairs.into_iter().enumerate() {
+                ensure!(kitty_id_1 != kitty_id_2, Error::<T>::BreedWithSelf);
+
+                let kitty1 = Self::owned_kitty(&sender, kitty_id_1)?;
+                let kitty2 = Self::owned_kitty(&sender, kitty_id_2)?;
+
+                Self::ensure_opposite_genders((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))?;
+
+                Self::do_breed(
+                    &sender,
+                    (kitty_id_1, kitty1),
+                    (kitty_id_2, kitty2),
+                    Some(index as u32),
+                )?;
+            }
+
+            Ok(())
+        }
+
+        /// Allow `partner` to breed one of their kitties with a kitty of the caller
+        #[pallet::weight(T::WeightInfo::approve_breeding())]
+        pub fn approve_breeding(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            partner: T::AccountId,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            BreedingApprovals::<T>::insert(&sender, kitty_id, &partner);
+
+            Self::deposit_event(Event::BreedingApproved(sender, partner, kitty_id));
+
+            Ok(())
+        }
+
+        /// Approve `partner` to breed their `partner_kitty_id` with a kitty of the caller and keep
+        /// that kitty for them up to and including `until_block`
+        ///
+        /// Until then the kitty can't change hands or breed with any other kitty. `BreedingDeposit`
+        /// is reserved until the breed happens or the kitty is released after `until_block`.
+        #[pallet::weight(T::WeightInfo::reserve_for_breeding())]
+        #[transactional]
+        pub fn reserve_for_breeding(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            partner: T::AccountId,
+            partner_kitty_id: u32,
+            until_block: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(kitty_id != partner_kitty_id, Error::<T>::BreedWithSelf);
+            Self::owned_kitty(&sender, kitty_id)?;
+            Self::owned_kitty(&partner, partner_kitty_id)?;
+            ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+            Self::ensure_not_reserved(kitty_id)?;
+            ensure!(
+                until_block >= <frame_system::Pallet<T>>::block_number(),
+                Error::<T>::ExpiryInPast
+            );
+
+            // A reservation that ran out gives its deposit back first
+            Self::release_breeding_lock(kitty_id);
+            let deposit = T::BreedingDeposit::get();
+            if !deposit.is_zero() {
+                T::Currency::reserve(&sender, deposit)
+                    .map_err(|_| Error::<T>::InsufficientBalance)?;
+                BreedingDeposits::<T>::insert(kitty_id, (&sender, deposit));
+            }
+
+            BreedingApprovals::<T>::insert(&sender, kitty_id, &partner);
+            Self::lock_for_breeding(kitty_id, partner_kitty_id, until_block);
+
+            Self::deposit_event(Event::BreedingReserved(
+                sender,
+                kitty_id,
+                partner_kitty_id,
+                until_block,
+            ));
+
+            Ok(())
+        }
+
+        /// Breed a kitty of the caller with an approved kitty of `partner`
+        ///
+        /// The offspring belongs to the caller and the approval is used up.
+        #[pallet::weight(
+            T::WeightInfo::breed_with().saturating_mul(Pallet::<T>::max_litter_size() as Weight)
+        )]
+        #[transactional]
+        pub fn breed_with(
+            origin: OriginFor<T>,
+            my_kitty_id: u32,
+            partner: T::AccountId,
+            partner_kitty_id: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(my_kitty_id != partner_kitty_id, Error::<T>::BreedWithSelf);
+
+            let my_kitty = Self::owned_kitty(&sender, my_kitty_id)?;
+            let partner_kitty = Self::owned_kitty(&partner, partner_kitty_id)?;
+
+            Self::ensure_opposite_genders(
+                (my_kitty_id, &my_kitty),
+                (partner_kitty_id, &partner_kitty),
+            )?;
+
+            ensure!(
+                BreedingApprovals::<T>::take(&partner, partner_kitty_id).as_ref() == Some(&sender),
+                Error::<T>::BreedingNotApproved
+            );
+
+            let kitty_id = Self::do_breed(
+                &sender,
+                (my_kitty_id, my_kitty),
+                (partner_kitty_id, partner_kitty),
+                None,
+            )?;
+
+            Self::deposit_event(Event::CoBred(sender, partner, kitty_id));
+
+            Ok(())
+        }
+
+        /// Transfer a kitty to another account
+        #[pallet::weight(T::WeightInfo::transfer())]
+        #[transactional]
+        pub fn transfer(origin: OriginFor<T>, to: T::AccountId, kitty_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(sender != to, Error::<T>::TransferToSelf);
+
+            Self::do_transfer(&sender, &to, kitty_id)
+        }
+
+        /// Transfer the given kitties of the caller to another account
+        ///
+        /// Nothing moves unless the caller owns every one of `kitty_ids`.
+        #[pallet::weight(T::WeightInfo::transfer_many(kitty_ids.len() as u32))]
+        #[transactional]
+        pub fn transfer_many(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_ids: Vec<u32>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(sender != to, Error::<T>::TransferToSelf);
+            let count = kitty_ids.len() as u32;
+            ensure!(count <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+            Self::ensure_owns_all(&sender, &kitty_ids)?;
+            ensure!(
+                Self::owned_count(&to).saturating_add(count) <= T::MaxKittiesOwned::get(),
+                Error::<T>::TooManyKitties
+            );
+
+            for kitty_id in kitty_ids {
+                Self::do_transfer(&sender, &to, kitty_id)?;
+            }
+
+            Ok(Some(T::WeightInfo::transfer_many(count)).into())
+        }
+
+        /// Transfer every kitty of the caller to another account
+        #[pallet::weight(T::WeightInfo::transfer_all(T::MaxKittiesOwned::get()))]
+        #[transactional]
+        pub fn transfer_all(origin: OriginFor<T>, to: T::AccountId) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(sender != to, Error::<T>::TransferToSelf);
+
+            let kitty_ids = Self::owned(&sender);
+            let count = kitty_ids.len() as u32;
+            ensure!(
+                Self::owned_count(&to).saturating_add(count) <= T::MaxKittiesOwned::get(),
+                Error::<T>::TooManyKitties
+            );
+
+            for kitty_id in kitty_ids.iter() {
+                Self::move_kitty(&sender, &to, *kitty_id)?;
+            }
+
+            Self::deposit_event(Event::AllKittiesTransferred(sender, to, count));
+
+            Ok(Some(T::WeightInfo::transfer_all(count)).into())
+        }
+
+        /// Burn a kitty
+        #[pallet::weight(T::WeightInfo::burn(T::MaxAttributes::get()))]
+        #[transactional]
+        pub fn burn(origin: OriginFor<T>, kitty_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::do_burn(&sender, kitty_id)
+        }
+
+        /// Remove up to `limit` graveyard entries older than `GraveyardRetention`
+        ///
+        /// Anyone may call this. Entries are visited oldest first and the walk stops at the
+        /// first one still within retention, so only the entries actually removed are charged.
+        #[pallet::weight(T::WeightInfo::cleanup_dead_kitties((*limit).min(T::MaxBatchSize::get())))]
+        #[transactional]
+        pub fn cleanup_dead_kitties(
+            origin: OriginFor<T>,
+            limit: u32,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            let limit = limit.min(T::MaxBatchSize::get());
+            let now = <frame_system::Pallet<T>>::block_number();
+            let retention = T::GraveyardRetention::get();
+            let (mut head, tail) = GraveyardRange::<T>::get();
+            let mut count = 0u32;
+            while count < limit && head < tail {
+                if let Some(kitty_id) = GraveyardQueue::<T>::get(head) {
+                    if let Some((_, died)) = DeadKitties::<T>::get(kitty_id) {
+                        if now.saturating_sub(died) < retention {
+                            break;
+                        }
+                    }
+                    DeadKitties::<T>::remove(kitty_id);
+                }
+                GraveyardQueue::<T>::remove(head);
+                head += 1;
+                count += 1;
+            }
+            GraveyardRange::<T>::put((head, tail));
+
+            Self::deposit_event(Event::GraveyardCleaned(count));
+
+            Ok(Some(T::WeightInfo::cleanup_dead_kitties(count)).into())
+        }
+
+        /// Give up a kitty so anyone can claim it
+        #[pallet::weight(T::WeightInfo::abandon())]
+        #[transactional]
+        pub fn abandon(origin: OriginFor<T>, kitty_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            let kitty = Self::owned_kitty(&sender, kitty_id)?;
+            ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+            Self::ensure_not_reserved(kitty_id)?;
+
+            Self::remove_from_owner(&sender, kitty_id)?;
+
+            // Abandoned kitties are left out of the counts until they are claimed again
+            KittyCount::<T>::try_mutate(|count| -> DispatchResult {
+                *count = count.checked_sub(1).ok_or(ArithmeticError::Underflow)?;
+                Ok(())
+            })?;
+            Self::decrease_gender_count(Self::resolve_gender(kitty_id, &kitty))?;
+
+            if let Some((depositor, deposit)) = KittyDeposits::<T>::take(kitty_id) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+
+            // The kitty stays alive, only its ownership and the owner's listings are dropped
+            Kitties::<T>::remove(kitty_id);
+            OwnerOf::<T>::remove(kitty_id);
+            Approvals::<T>::remove(kitty_id);
+            Self::remove_name(&sender, kitty_id);
+            KittyPrices::<T>::remove(&sender, kitty_id);
+            ListingExpiry::<T>::remove(&sender, kitty_id);
+            BreedingApprovals::<T>::remove(&sender, kitty_id);
+            SwapApprovals::<T>::remove(&sender, kitty_id);
+            Self::release_breeding_lock(kitty_id);
+            OwnerDna::<T>::remove(&sender, kitty.as_bytes());
+            AbandonedKitties::<T>::insert(kitty_id, kitty);
+
+            Self::deposit_event(Event::KittyAbandoned(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Take ownership of an abandoned kitty
+        #[pallet::weight(T::WeightInfo::claim())]
+        #[transactional]
+        pub fn claim(origin: OriginFor<T>, kitty_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            let kitty =
+                AbandonedKitties::<T>::take(kitty_id).ok_or(Error::<T>::KittyNotAbandoned)?;
+
+            Self::add_to_owner(&sender, kitty_id)?;
+            Self::reserve_deposit(&sender, kitty_id)?;
+
+            KittyCount::<T>::try_mutate(|count| -> DispatchResult {
+                *count = count.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+            Self::increase_gender_count(Self::resolve_gender(kitty_id, &kitty))?;
+
+            OwnerDna::<T>::insert(&sender, kitty.as_bytes(), ());
+            Kitties::<T>::insert(kitty_id, kitty);
+            OwnerOf::<T>::insert(kitty_id, &sender);
+            Self::record_owner(kitty_id, &sender);
+
+            Self::deposit_event(Event::KittyClaimed(sender, kitty_id));
+
+            Ok(())
+        }
+
+        /// Set the name of a kitty, paying `RenameFee`
+        #[pallet::weight(T::WeightInfo::set_name())]
+        #[transactional]
+        pub fn set_name(origin: OriginFor<T>, kitty_id: u32, name: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            let bounded_name: BoundedVec<u8, T::MaxNameLength> = name
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::NameTooLong)?;
+
+            // Renaming a kitty to the name it already has changes nothing
+            let old_name = Self::kitty_names(&sender, kitty_id);
+            if old_name.as_ref() == Some(&bounded_name) {
+                return Ok(());
+            }
+            let unique_names = T::UniqueNames::get();
+            if unique_names {
+                ensure!(
+                    !NameOwner::<T>::contains_key(&bounded_name),
+                    Error::<T>::NameTaken
+                );
+            }
+
+            let fee = T::RenameFee::get();
+            if !fee.is_zero() {
+                T::Currency::transfer(
+                    &sender,
+                    &T::FeeCollector::get(),
+                    fee,
+                    ExistenceRequirement::KeepAlive,
+                )?;
+            }
+            if unique_names {
+                if let Some(old_name) = old_name {
+                    NameOwner::<T>::remove(old_name);
+                }
+                NameOwner::<T>::insert(&bounded_name, kitty_id);
+            }
+            KittyNames::<T>::insert(&sender, kitty_id, bounded_name);
+
+            Self::deposit_event(Event::KittyRenamed(sender, kitty_id, name, fee));
+
+            Ok(())
+        }
+
+        /// Make a kitty unable to breed, this can't be undone
+        #[pallet::weight(T::WeightInfo::set_sterile())]
+        pub fn set_sterile(origin: OriginFor<T>, kitty_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            SterileKitties::<T>::insert(kitty_id, ());
+
+            Self::deposit_event(Event::KittySterilized(kitty_id));
+
+            Ok(())
+        }
+
+        /// Set the attribute `key` of a kitty to `value`, replacing any previous value
+        #[pallet::weight(T::WeightInfo::set_attribute())]
+        pub fn set_attribute(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            key: Vec<u8>,
+            value: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            let bounded_key: BoundedVec<u8, T::MaxKeyLen> =
+                key.clone().try_into().map_err(|_| Error::<T>::KeyTooLong)?;
+            let bounded_value: BoundedVec<u8, T::MaxValueLen> = value
+                .clone()
+                .try_into()
+                .map_err(|_| Error::<T>::ValueTooLong)?;
+
+            if !KittyAttributes::<T>::contains_key(kitty_id, &bounded_key) {
+                AttributeCount::<T>::try_mutate(kitty_id, |count| -> DispatchResult {
+                    ensure!(
+                        *count < T::MaxAttributes::get(),
+                        Error::<T>::TooManyAttributes
+                    );
+                    *count += 1;
+                    Ok(())
+                })?;
+            }
+            KittyAttributes::<T>::insert(kitty_id, bounded_key, bounded_value);
+
+            Self::deposit_event(Event::AttributeSet(kitty_id, key, value));
+
+            Ok(())
+        }
+
+        /// Remove the attribute `key` of a kitty
+        #[pallet::weight(T::WeightInfo::clear_attribute())]
+        pub fn clear_attribute(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            key: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            let bounded_key: BoundedVec<u8, T::MaxKeyLen> =
+                key.clone().try_into().map_err(|_| Error::<T>::KeyTooLong)?;
+            KittyAttributes::<T>::take(kitty_id, &bounded_key)
+                .ok_or(Error::<T>::AttributeNotFound)?;
+            AttributeCount::<T>::mutate(kitty_id, |count| *count = count.saturating_sub(1));
+
+            Self::deposit_event(Event::AttributeCleared(kitty_id, key));
+
+            Ok(())
+        }
+
+        /// Set the price of a kitty, `None` delists it
+        ///
+        /// A listing with an `expiry_block` is removed again once that block is reached.
+        #[pallet::weight(T::WeightInfo::set_price())]
+        pub fn set_price(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            price: Option<BalanceOf<T>>,
+            expiry_block: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+
+            let expiry_block = expiry_block.filter(|_| price.is_some());
+            if let Some(expiry_block) = expiry_block {
+                ensure!(
+                    expiry_block > <frame_system::Pallet<T>>::block_number(),
+                    Error::<T>::ExpiryInPast
+                );
+                ExpiringListings::<T>::try_mutate(expiry_block, |listings| {
+                    listings.try_push((sender.clone(), kitty_id))
+                })
+                .map_err(|_| Error::<T>::TooManyExpiringListings)?;
+            }
+
+            KittyPrices::<T>::mutate_exists(&sender, kitty_id, |current| *current = price);
+            ListingExpiry::<T>::mutate_exists(&sender, kitty_id, |current| *current = expiry_block);
+
+            Self::deposit_event(Event::KittyPriceUpdated(sender, kitty_id, price));
+
+            Ok(())
+        }
+
+        /// Buy a listed kitty from its owner
+        #[pallet::weight(T::WeightInfo::buy())]
+        #[transactional]
+        pub fn buy(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            kitty_id: u32,
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(sender != owner, Error::<T>::BuyFromSelf);
+
+            let price = Self::kitty_prices(&owner, kitty_id).ok_or(Error::<T>::NotForSale)?;
+            ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+            // Tiny prices round the fee down to nothing, the seller gets everything then
+            let fee = T::MarketplaceFee::get().mul_floor(price);
+            if !fee.is_zero() {
+                T::Currency::transfer(
+                    &sender,
+                    &T::FeeCollector::get(),
+                    fee,
+                    ExistenceRequirement::KeepAlive,
+                )?;
+            }
+            T::Currency::transfer(
+                &sender,
+                &owner,
+                price.saturating_sub(fee),
+                ExistenceRequirement::KeepAlive,
+            )?;
+
+            Self::do_transfer(&owner, &sender, kitty_id)?;
+
+            Self::deposit_event(Event::KittySold(owner, sender, kitty_id, price, fee));
+
+            Ok(())
+        }
+
+        /// Auction a kitty off, locking it until the auction is closed
+        #[pallet::weight(T::WeightInfo::start_auction())]
+        pub fn start_auction(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            min_bid: BalanceOf<T>,
+            duration: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, kitty_id)?;
+            ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+            Self::ensure_not_reserved(kitty_id)?;
+
+            let end_block = <frame_system::Pallet<T>>::block_number().saturating_add(duration);
+
+            Self::lock_kitty(kitty_id);
+            KittyAuctions::<T>::insert(
+                kitty_id,
+                Auction {
+                    seller: sender.clone(),
+                    highest_bid: min_bid,
+                    highest_bidder: None,
+                    end_block,
+                },
+            );
+
+            Self::deposit_event(Event::AuctionStarted(sender, kitty_id, min_bid, end_block));
+
+            Ok(())
+        }
+
+        /// Bid on an auctioned kitty, reserving the bid and refunding the previous bidder
+        #[pallet::weight(T::WeightInfo::bid())]
+        #[transactional]
+        pub fn bid(origin: OriginFor<T>, kitty_id: u32, amount: BalanceOf<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            let mut auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() < auction.end_block,
+                Error::<T>::AuctionEnded
+            );
+            ensure!(sender != auction.seller, Error::<T>::BuyFromSelf);
+            ensure!(
+                Self::owned_count(&sender) < T::MaxKittiesOwned::get(),
+                Error::<T>::TooManyKitties
+            );
+
+            // The first bid may match the minimum, later bids have to outbid
+            let enough = match auction.highest_bidder {
+                Some(_) => amount > auction.highest_bid,
+                None => amount >= auction.highest_bid,
+            };
+            ensure!(enough, Error::<T>::BidTooLow);
+
+            T::Currency::reserve(&sender, amount).map_err(|_| Error::<T>::InsufficientBalance)?;
+            if let Some(previous) = auction.highest_bidder.take() {
+                T::Currency::unreserve(&previous, auction.highest_bid);
+            }
+
+            auction.highest_bid = amount;
+            auction.highest_bidder = Some(sender.clone());
+            KittyAuctions::<T>::insert(kitty_id, auction);
+
+            Self::deposit_event(Event::BidPlaced(sender, kitty_id, amount));
+
+            Ok(())
+        }
+
+        /// Settle an auction after its end block, paying the seller and moving the kitty
+        #[pallet::weight(T::WeightInfo::close_auction())]
+        #[transactional]
+        pub fn close_auction(origin: OriginFor<T>, kitty_id: u32) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            let auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= auction.end_block,
+                Error::<T>::AuctionNotEnded
+            );
+
+            KittyAuctions::<T>::remove(kitty_id);
+            Self::unlock_kitty(kitty_id);
+
+            // A bidder who filled up on kitties since bidding can't receive this one, the
+            // auction then ends without a sale and the bid is returned
+            let winner = auction
+                .highest_bidder
+                .clone()
+                .filter(|bidder| Self::owned_count(bidder) < T::MaxKittiesOwned::get());
+            if let Some(winner) = &winner {
+                T::Currency::repatriate_reserved(
+                    winner,
+                    &auction.seller,
+                    auction.highest_bid,
+                    BalanceStatus::Free,
+                )?;
+                Self::do_transfer(&auction.seller, winner, kitty_id)?;
+            } else if let Some(bidder) = &auction.highest_bidder {
+                T::Currency::unreserve(bidder, auction.highest_bid);
+            }
+
+            Self::deposit_event(Event::AuctionClosed(kitty_id, winner, auction.highest_bid));
+
+            Ok(())
+        }
+
+        /// Approve an operator to transfer a kitty on behalf of its owner
+        #[pallet::weight(T::WeightInfo::approve())]
+        pub fn approve(
+            origin: OriginFor<T>,
+            operator: T::AccountId,
+            kitty_id: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(
+                Self::owner_of(kitty_id).as_ref() == Some(&sender),
+                Error::<T>::NotOwner
+            );
+
+            Approvals::<T>::insert(kitty_id, &operator);
+
+            Self::deposit_event(Event::Approval(sender, operator, kitty_id));
+
+            Ok(())
+        }
+
+        /// Transfer a kitty as its owner or its approved operator
+        #[pallet::weight(T::WeightInfo::transfer_from())]
+        #[transactional]
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(from != to, Error::<T>::TransferToSelf);
+            ensure!(
+                Self::owner_of(kitty_id).as_ref() == Some(&from),
+                Error::<T>::NotOwner
+            );
+            ensure!(
+                sender == from || Self::approvals(kitty_id).as_ref() == Some(&sender),
+                Error::<T>::NotApproved
+            );
+
+            Self::do_transfer(&from, &to, kitty_id)
+        }
+
+        /// Agree to give a kitty of the caller to `counterparty` for their `their_kitty_id`
+        #[pallet::weight(T::WeightInfo::approve_swap())]
+        pub fn approve_swap(
+            origin: OriginFor<T>,
+            my_kitty_id: u32,
+            counterparty: T::AccountId,
+            their_kitty_id: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::owned_kitty(&sender, my_kitty_id)?;
+
+            SwapApprovals::<T>::insert(&sender, my_kitty_id, (&counterparty, their_kitty_id));
+
+            Self::deposit_event(Event::SwapApproved(
+                sender,
+                my_kitty_id,
+                counterparty,
+                their_kitty_id,
+            ));
+
+            Ok(())
+        }
+
+        /// Exchange a kitty of the caller for a kitty of `counterparty`
+        ///
+        /// The counterparty must have approved exactly this swap, the approval is used up.
+        #[pallet::weight(T::WeightInfo::swap())]
+        #[transactional]
+        pub fn swap(
+            origin: OriginFor<T>,
+            my_kitty_id: u32,
+            counterparty: T::AccountId,
+            their_kitty_id: u32,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(sender != counterparty, Error::<T>::TransferToSelf);
+
+            let my_kitty = Self::owned_kitty(&sender, my_kitty_id)?;
+            let their_kitty = Self::owned_kitty(&counterparty, their_kitty_id)?;
+            for kitty_id in &[my_kitty_id, their_kitty_id] {
+                ensure!(!Self::is_dead(*kitty_id), Error::<T>::KittyDead);
+                ensure!(!Self::is_locked(*kitty_id), Error::<T>::KittyLocked);
+                Self::ensure_not_reserved(*kitty_id)?;
+            }
+
+            ensure!(
+                SwapApprovals::<T>::take(&counterparty, their_kitty_id)
+                    == Some((sender.clone(), my_kitty_id)),
+                Error::<T>::SwapNotApproved
+            );
+
+            // Both kitties leave before either arrives, so owners at their cap can still swap
+            Self::remove_from_owner(&sender, my_kitty_id)?;
+            Self::remove_from_owner(&counterparty, their_kitty_id)?;
+            Self::add_to_owner(&counterparty, my_kitty_id)?;
+            Self::add_to_owner(&sender, their_kitty_id)?;
+
+            Self::reassign_kitty(&sender, &counterparty, my_kitty_id, &my_kitty);
+            Self::reassign_kitty(&counterparty, &sender, their_kitty_id, &their_kitty);
+
+            Self::deposit_event(Event::KittiesSwapped(
+                sender,
+                counterparty,
+                my_kitty_id,
+                their_kitty_id,
+            ));
+
+            Ok(())
+        }
+
+        /// Pause or resume every extrinsic of the pallet except the privileged ones
+        #[pallet::weight(T::WeightInfo::set_paused())]
+        pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            Paused::<T>::put(paused);
+
+            Self::deposit_event(Event::PalletPauseToggled(paused));
+
+            Ok(())
+        }
+
+        /// Pause or resume breeding for everyone
+        #[pallet::weight(T::WeightInfo::set_breeding_paused())]
+        pub fn set_breeding_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            BreedingPaused::<T>::put(paused);
+
+            Self::deposit_event(Event::BreedingPauseToggled(paused));
+
+            Ok(())
+        }
+
+        /// Set the gender of a kitty regardless of its DNA
+        #[pallet::weight(T::WeightInfo::admin_set_gender())]
+        pub fn admin_set_gender(
+            origin: OriginFor<T>,
+            kitty_id: u32,
+            gender: KittyGender,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let kitty = Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+            let old_gender = Self::resolve_gender(kitty_id, &kitty);
+            if old_gender != gender {
+                Self::decrease_gender_count(old_gender)?;
+                Self::increase_gender_count(gender)?;
+            }
+
+            GenderOverride::<T>::insert(kitty_id, gender);
+
+            Self::deposit_event(Event::GenderOverridden(kitty_id, gender));
+
+            Ok(())
+        }
+
+        /// Move a kitty from `from` to `to` without the consent of `from`
+        ///
+        /// Locks and approvals are ignored, a running auction of the kitty is called off and its
+        /// highest bid refunded. A breeding reservation that still holds is broken and its deposit
+        /// slashed. The ownership cap of `to` still applies.
+        #[pallet::weight(T::WeightInfo::force_transfer())]
+        #[transactional]
+        pub fn force_transfer(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: u32,
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            ensure!(from != to, Error::<T>::TransferToSelf);
+
+            let kitty = Self::owned_kitty(&from, kitty_id)?;
+
+            if let Some(auction) = KittyAuctions::<T>::take(kitty_id) {
+                if let Some(bidder) = &auction.highest_bidder {
+                    T::Currency::unreserve(bidder, auction.highest_bid);
+                }
+                Self::deposit_event(Event::AuctionCancelled(
+                    kitty_id,
+                    auction.highest_bidder,
+                    auction.highest_bid,
+                ));
+            }
+            Self::unlock_kitty(kitty_id);
+            // Taking a reserved kitty away from its partner breaks the reservation
+            if Self::breeding_partner(kitty_id).is_some() {
+                Self::slash_breeding_deposit(kitty_id);
+            }
+
+            Self::add_to_owner(&to, kitty_id)?;
+            Self::remove_from_owner(&from, kitty_id)?;
+
+            Self::reassign_kitty(&from, &to, kitty_id, &kitty);
+
+            Self::deposit_event(Event::KittyForceTransferred(from, to, kitty_id));
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether a kitty with `kitty_id` exists.
+        pub fn kitty_exists(kitty_id: u32) -> bool {
+            Kitties::<T>::contains_key(kitty_id)
+        }
+
+        /// Mint a kitty with the given DNA for `owner`, skipping randomness.
+        ///
+        /// Only meant to set up exact scenarios in tests, never compiled into a production runtime.
+        #[cfg(any(test, feature = "test-helpers"))]
+        #[transactional]
+        pub fn create_with_dna(owner: &T::AccountId, dna: [u8; 16]) -> Result<u32, DispatchError> {
+            let kitty = Kitty::from_bytes(dna);
+            let kitty_id = Self::mint(owner, &kitty, owner)?;
+            TotalCreated::<T>::try_mutate(|total| -> DispatchResult {
+                *total = total.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            let gender = kitty.gender();
+            Self::deposit_event_with_topics(
+                Event::KittyCreated(owner.clone(), kitty_id, kitty, gender),
+                kitty_id,
+                &[owner],
+            );
+
+            Ok(kitty_id)
+        }
+
+        /// The kitty stored under `kitty_id`, whoever owns it.
+        pub fn kitty_by_id(kitty_id: u32) -> Option<Kitty> {
+            Self::kitties(kitty_id)
+        }
+
+        /// Whether `kitty_id` is owned by `owner`.
+        pub fn is_owner(owner: &T::AccountId, kitty_id: u32) -> bool {
+            Self::owner_of(kitty_id).as_ref() == Some(owner)
+        }
+
+        /// Fail with `InvalidKittyId` on the first of `ids` not owned by `owner`.
+        pub fn ensure_owns_all(owner: &T::AccountId, ids: &[u32]) -> DispatchResult {
+            for kitty_id in ids {
+                ensure!(Self::is_owner(owner, *kitty_id), Error::<T>::InvalidKittyId);
+            }
+            Ok(())
+        }
+
+        /// The number of male and female kitties of `owner`, honouring admin overrides.
+        ///
+        /// Reads every kitty of `owner`, up to `MaxKittiesOwned` lookups, so it is meant for
+        /// runtime API and off-chain use, not for extrinsics.
+        pub fn owner_gender_ratio(owner: &T::AccountId) -> (u32, u32) {
+            let mut ratio = (0, 0);
+            for kitty_id in Self::owned(owner).iter() {
+                match Self::gender_of(*kitty_id) {
+                    Some(KittyGender::Male) => ratio.0 += 1,
+                    Some(KittyGender::Female) => ratio.1 += 1,
+                    None => {}
+                }
+            }
+            ratio
+        }
+
+        /// Aggregate gender and rarity figures over all kitties, meant for off-chain use.
+        pub fn rarity_stats() -> RarityStats {
+            let mut stats = RarityStats::default();
+            for (kitty_id, kitty) in Kitties::<T>::iter() {
+                stats.count += 1;
+                match Self::resolve_gender(kitty_id, &kitty) {
+                    KittyGender::Male => stats.males += 1,
+                    KittyGender::Female => stats.females += 1,
+                }
+                let rarity = kitty.rarity_score();
+                stats.total_rarity += rarity as u64;
+                stats.max_rarity = stats.max_rarity.max(rarity);
+            }
+            stats
+        }
+
+        /// The kitty of `owner` with the highest rarity score. Ties go to the lowest id.
+        pub fn rarest_owned(owner: &T::AccountId) -> Option<u32> {
+            Self::owned(owner)
+                .iter()
+                .filter_map(|id| Self::kitties(id).map(|kitty| (kitty.rarity_score(), *id)))
+                .max_by(|(score_a, id_a), (score_b, id_b)| {
+                    score_a.cmp(score_b).then(id_b.cmp(id_a))
+                })
+                .map(|(_, id)| id)
+        }
+
+        /// Up to `limit` kitties of `owner` ordered by id, starting at `start_key`.
+        ///
+        /// Also returns the id to pass as `start_key` for the next page, if any.
+        pub fn kitties_paged(
+            owner: &T::AccountId,
+            start_key: Option<u32>,
+            limit: u32,
+        ) -> (Vec<(u32, Kitty)>, Option<u32>) {
+            let mut ids = Self::owned(owner).to_vec();
+            ids.sort_unstable();
+
+            let start = start_key.unwrap_or_default();
+            let mut remaining = ids.into_iter().filter(|id| *id >= start);
+
+            let page = remaining
+                .by_ref()
+                .take(limit as usize)
+                .filter_map(|id| Self::kitties(id).map(|kitty| (id, kitty)))
+                .collect();
+
+            (page, remaining.next())
+        }
+
+        /// Up to `limit` ids of living kitties in ascending order, starting at `start`.
+        ///
+        /// Kitties are stored by id, so this walks the ids from `start` up to `NextKittyId`
+        /// with one lookup per id. Burned and abandoned ids are read and skipped as well, so it
+        /// is meant for runtime API use only.
+        pub fn all_kitty_ids(start: u32, limit: u32) -> Vec<u32> {
+            (start..Self::next_kitty_id())
+                .filter(|id| Self::kitty_exists(*id))
+                .take(limit as usize)
+                .collect()
+        }
+
+        /// Up to `limit` listed kitties as (kitty_id, seller, price) in ascending id order, from
+        /// `start` on.
+        ///
+        /// There is no separate index of listings: `KittyPrices` only holds listed kitties, so
+        /// this iterates and sorts it. That costs one read per listing and is meant for runtime
+        /// API use only.
+        pub fn listed_kitties(start: u32, limit: u32) -> Vec<(u32, T::AccountId, BalanceOf<T>)> {
+            let mut listed: Vec<_> = KittyPrices::<T>::iter()
+                .filter(|(_, kitty_id, _)| *kitty_id >= start)
+                .map(|(seller, kitty_id, price)| (kitty_id, seller, price))
+                .collect();
+            listed.sort_by_key(|(kitty_id, _, _)| *kitty_id);
+            listed.truncate(limit as usize);
+            listed
+        }
+
+        /// Collect the kitty `kitty_id` and its metadata, if it is owned by `owner`.
+        pub fn kitty_info(
+            owner: &T::AccountId,
+            kitty_id: u32,
+        ) -> Option<KittyInfo<T::BlockNumber>> {
+            if Self::owner_of(kitty_id).as_ref() != Some(owner) {
+                return None;
+            }
+            let kitty = Self::kitties(kitty_id)?;
+
+            Some(KittyInfo {
+                dna: *kitty.as_bytes(),
+                gender: Self::resolve_gender(kitty_id, &kitty),
+                generation: Self::generation_of(kitty_id),
+                birth_block: Self::birth_block(kitty_id),
+                parents: Self::parents_of(kitty_id),
+            })
+        }
+
+        /// A UTF-8 summary like "Female, Generation 3, Rare (score 12)" of the kitty `kitty_id`,
+        /// if it is owned by `owner`.
+        pub fn describe_kitty(owner: &T::AccountId, kitty_id: u32) -> Option<Vec<u8>> {
+            let info = Self::kitty_info(owner, kitty_id)?;
+            let kitty = Kitty::from_bytes(info.dna);
+
+            let gender = match info.gender {
+                KittyGender::Male => "Male",
+                KittyGender::Female => "Female",
+            };
+
+            let mut description = Vec::new();
+            description.extend_from_slice(gender.as_bytes());
+            description.extend_from_slice(b", Generation ");
+            push_decimal(&mut description, info.generation);
+            description.extend_from_slice(b", ");
+            description.extend_from_slice(kitty.rarity_tier().as_bytes());
+            description.extend_from_slice(b" (score ");
+            push_decimal(&mut description, kitty.rarity_score());
+            description.push(b')');
+
+            Some(description)
+        }
+
+        /// The DNA distance between two existing kitties, whoever owns them.
+        pub fn breeding_compatibility(kitty_id_1: u32, kitty_id_2: u32) -> Option<u32> {
+            let kitty_1 = Self::kitties(kitty_id_1)?;
+            let kitty_2 = Self::kitties(kitty_id_2)?;
+            Some(dna_distance(&kitty_1, &kitty_2))
+        }
+
+        /// The kitty `owner` would get from breeding `kitty_id_1` with `kitty_id_2` if the
+        /// randomness produced `selector`, without writing anything.
+        ///
+        /// Runs the same checks as `breed`. A real breeding draws a fresh selector, so this is
+        /// only a preview.
+        pub fn simulate_breed(
+            owner: &T::AccountId,
+            kitty_id_1: u32,
+            kitty_id_2: u32,
+            selector: [u8; 16],
+        ) -> Result<Kitty, Error<T>> {
+            ensure!(kitty_id_1 != kitty_id_2, Error::<T>::BreedWithSelf);
+
+            let owned = |kitty_id: u32| {
+                Self::kitties(kitty_id)
+                    .filter(|_| Self::owner_of(kitty_id).as_ref() == Some(owner))
+                    .ok_or(Error::<T>::InvalidKittyId)
+            };
+            let kitty1 = owned(kitty_id_1)?;
+            let kitty2 = owned(kitty_id_2)?;
+
+            ensure!(
+                Self::resolve_gender(kitty_id_1, &kitty1)
+                    == Self::resolve_gender(kitty_id_2, &kitty2).opposite(),
+                Error::<T>::SameGender
+            );
+
+            Self::ensure_can_breed((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))?;
+
+            let mut dna = Self::mix_parents(&kitty1, &kitty2, &selector);
+            Self::mutate(&mut dna, &selector);
+            Ok(Kitty::from_bytes(dna))
+        }
+
+        /// Destroy `kitty_id` owned by `owner` and release its deposit.
+        pub(crate) fn do_burn(owner: &T::AccountId, kitty_id: u32) -> DispatchResult {
+            let kitty = Self::owned_kitty(owner, kitty_id)?;
+            ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+            Self::ensure_not_reserved(kitty_id)?;
+
+            KittyCount::<T>::try_mutate(|count| -> DispatchResult {
+                *count = count.checked_sub(1).ok_or(ArithmeticError::Underflow)?;
+                Ok(())
+            })?;
+            Self::remove_from_owner(owner, kitty_id)?;
+
+            if let Some((depositor, deposit)) = KittyDeposits::<T>::take(kitty_id) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+
+            Kitties::<T>::remove(kitty_id);
+            OwnerOf::<T>::remove(kitty_id);
+            Approvals::<T>::remove(kitty_id);
+            Self::remove_name(owner, kitty_id);
+            KittyPrices::<T>::remove(owner, kitty_id);
+            ListingExpiry::<T>::remove(owner, kitty_id);
+            BreedingApprovals::<T>::remove(owner, kitty_id);
+            SwapApprovals::<T>::remove(owner, kitty_id);
+            Self::release_breeding_lock(kitty_id);
+            // Bounded by `MaxAttributes`
+            KittyAttributes::<T>::drain_prefix(kitty_id).for_each(drop);
+            AttributeCount::<T>::remove(kitty_id);
+            Self::decrease_gender_count(Self::resolve_gender(kitty_id, &kitty))?;
+            GenderOverride::<T>::remove(kitty_id);
+            SterileKitties::<T>::remove(kitty_id);
+            DnaExists::<T>::remove(kitty.as_bytes());
+            OwnerDna::<T>::remove(owner, kitty.as_bytes());
+            KittyHistory::<T>::remove(kitty_id);
+            Self::clear_life_record(kitty_id);
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            Self::bury(owner, kitty_id, now);
+
+            Self::deposit_event(Event::KittyBurned(owner.clone(), kitty_id));
+            Self::deposit_event(Event::KittyBuried(kitty_id, owner.clone(), now));
+
+            Ok(())
+        }
+
+        /// Drop the lineage, breeding record and birth block stored for `kitty_id`.
+        fn clear_life_record(kitty_id: u32) {
+            KittyParents::<T>::remove(kitty_id);
+            KittyGeneration::<T>::remove(kitty_id);
+            KittyBirthBlock::<T>::remove(kitty_id);
+            KittyCooldown::<T>::remove(kitty_id);
+            BreedCount::<T>::remove(kitty_id);
+        }
+
+        /// Record the death of `kitty_id` and queue it for `cleanup_dead_kitties`.
+        pub(crate) fn bury(owner: &T::AccountId, kitty_id: u32, now: T::BlockNumber) {
+            DeadKitties::<T>::insert(kitty_id, (owner.clone(), now));
+            GraveyardRange::<T>::mutate(|(_, tail)| {
+                GraveyardQueue::<T>::insert(*tail, kitty_id);
+                *tail += 1;
+            });
+        }
+
+        /// Store `kitty` under the next free id for `owner` and return that id. The kitty deposit
+        /// is reserved from `depositor`.
+        fn mint(
+            owner: &T::AccountId,
+            kitty: &Kitty,
+            depositor: &T::AccountId,
+        ) -> Result<u32, DispatchError> {
+            ensure!(
+                Self::next_kitty_id() < T::MaxSupply::get(),
+                Error::<T>::MaxSupplyReached
+            );
+            let kitty_id = NextKittyId::<T>::try_mutate(|next_id| -> Result<u32, DispatchError> {
+                let current_id = *next_id;
+                *next_id = next_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(current_id)
+            })?;
+            Self::check_id_threshold(kitty_id + 1);
+
+            Self::mint_at(owner, kitty_id, kitty, depositor)?;
+
+            Ok(kitty_id)
+        }
+
+        /// Store `kitty` under `kitty_id` for `owner`, reserving the kitty deposit from
+        /// `depositor`. The id must not be taken and has to be accounted for in `NextKittyId` by
+        /// the caller.
+        pub(crate) fn mint_at(
+            owner: &T::AccountId,
+            kitty_id: u32,
+            kitty: &Kitty,
+            depositor: &T::AccountId,
+        ) -> DispatchResult {
+            ensure!(
+                !DnaExists::<T>::contains_key(kitty.as_bytes()),
+                Error::<T>::DuplicateDna
+            );
+            Self::index_kitty(owner, kitty_id, kitty)?;
+            Self::reserve_deposit(depositor, kitty_id)?;
+
+            // An id freed by `cleanup_dead_kitties` starts over
+            Self::clear_life_record(kitty_id);
+            Kitties::<T>::insert(kitty_id, kitty);
+            OwnerOf::<T>::insert(kitty_id, owner);
+            KittyBirthBlock::<T>::insert(kitty_id, <frame_system::Pallet<T>>::block_number());
+            LastCreatedKitty::<T>::insert(owner, kitty_id);
+            AccountNonce::<T>::try_mutate(owner, |nonce| -> DispatchResult {
+                *nonce = nonce.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            Ok(())
+        }
+
+        /// The random material every DNA minted for `owner` in this extrinsic is derived from.
+        ///
+        /// The block number and the nonce of `owner` keep it apart from earlier calls even when
+        /// the randomness source returns the same seed.
+        pub(crate) fn random_payload(owner: &T::AccountId) -> Result<Vec<u8>, Error<T>> {
+            Ok((
+                Self::random_seed()?,
+                owner,
+                <frame_system::Pallet<T>>::extrinsic_index(),
+                <frame_system::Pallet<T>>::block_number(),
+                Self::account_nonce(owner),
+            )
+                .encode())
+        }
+
+        /// The seed of `Randomness`, failing if it is the zero hash.
+        ///
+        /// A chain without a working randomness source hands out zeroes, which would make every
+        /// DNA predictable.
+        pub(crate) fn random_seed() -> Result<T::Hash, Error<T>> {
+            let seed = T::Randomness::random_seed().0;
+            ensure!(
+                seed != T::Hash::default(),
+                Error::<T>::RandomnessNotAvailable
+            );
+            Ok(seed)
+        }
+
+        /// Generate a random 128bit DNA for a new kitty of `owner`.
+        pub(crate) fn generate_dna(owner: &T::AccountId) -> Result<[u8; 16], Error<T>> {
+            Ok(blake2_128(&Self::random_payload(owner)?))
+        }
+
+        /// The selector picking the genes of litter sibling `litter_index` out of `payload`.
+        ///
+        /// Siblings mix in their index so they don't all get the same DNA.
+        pub(crate) fn breeding_selector(payload: &[u8], litter_index: u32) -> [u8; 16] {
+            if litter_index == 0 {
+                return blake2_128(payload);
+            }
+            let mut payload = payload.to_vec();
+            litter_index.encode_to(&mut payload);
+            blake2_128(&payload)
+        }
+
+        /// Fill the DNA, gender, owner and count indexes for `kitty_id` owned by `owner`.
+        ///
+        /// Used by `mint_at`, the genesis build and migrations moving kitties stored without
+        /// these indexes.
+        pub(crate) fn index_kitty(
+            owner: &T::AccountId,
+            kitty_id: u32,
+            kitty: &Kitty,
+        ) -> DispatchResult {
+            DnaExists::<T>::insert(kitty.as_bytes(), ());
+            OwnerDna::<T>::insert(owner, kitty.as_bytes(), ());
+            Self::increase_gender_count(kitty.gender())?;
+
+            Self::add_to_owner(owner, kitty_id)?;
+
+            KittyCount::<T>::try_mutate(|count| -> DispatchResult {
+                *count = count.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            Self::record_owner(kitty_id, owner);
+            Ok(())
+        }
+
+        /// Mint a kitty with random DNA for `owner`.
+        fn create_random(owner: &T::AccountId) -> Result<u32, DispatchError> {
+            Self::create_random_at(owner, None, owner)
+        }
+
+        /// Mint a kitty with random DNA for `owner` under `kitty_id`, or the next free id if
+        /// `None`, returning the id used. The kitty deposit is reserved from `depositor`.
+        ///
+        /// A given `kitty_id` must not be taken and has to be accounted for in `NextKittyId` by
+        /// the caller.
+        pub(crate) fn create_random_at(
+            owner: &T::AccountId,
+            kitty_id: Option<u32>,
+            depositor: &T::AccountId,
+        ) -> Result<u32, DispatchError> {
+            Self::ensure_randomness_ready()?;
+
+            let dna = Self::generate_dna(owner)?;
+
+            ensure!(
+                !OwnerDna::<T>::contains_key(owner, &dna),
+                Error::<T>::DuplicateDnaForOwner
+            );
+
+            // Create and store kitty
+            let kitty = Kitty::from_bytes(dna);
+            let kitty_id = match kitty_id {
+                Some(kitty_id) => {
+                    Self::mint_at(owner, kitty_id, &kitty, depositor)?;
+                    kitty_id
+                }
+                None => Self::mint(owner, &kitty, depositor)?,
+            };
+            TotalCreated::<T>::try_mutate(|total| -> DispatchResult {
+                *total = total.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            // Emit event
+            let gender = kitty.gender();
+            Self::deposit_event_with_topics(
+                Event::KittyCreated(owner.clone(), kitty_id, kitty, gender),
+                kitty_id,
+                &[owner],
+            );
+
+            T::OnKittyCreated::on_created(owner, kitty_id);
+
+            Ok(kitty_id)
+        }
+
+        /// Breed `parent1` with `parent2` and mint the offspring for `sender`, returning the id of
+        /// the first one.
+        ///
+        /// Ownership and genders of both parents must be checked by the caller. `batch_index`
+        /// is the position of the pair when several are bred in the same extrinsic.
+        fn do_breed(
+            sender: &T::AccountId,
+            parent1: (u32, Kitty),
+            parent2: (u32, Kitty),
+            batch_index: Option<u32>,
+        ) -> Result<u32, DispatchError> {
+            let (kitty_id_1, kitty1) = parent1;
+            let (kitty_id_2, kitty2) = parent2;
+
+            Self::ensure_can_breed((kitty_id_1, &kitty1), (kitty_id_2, &kitty2))?;
+            Self::charge_breed_fee(sender)?;
+
+            // A reservation is completed by breeding with its partner
+            Self::release_breeding_lock(kitty_id_1);
+            Self::release_breeding_lock(kitty_id_2);
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            let cooldown = now.saturating_add(T::BreedCooldownPeriod::get());
+            KittyCooldown::<T>::insert(kitty_id_1, cooldown);
+            KittyCooldown::<T>::insert(kitty_id_2, cooldown);
+
+            for kitty_id in &[kitty_id_1, kitty_id_2] {
+                BreedCount::<T>::try_mutate(kitty_id, |count| -> DispatchResult {
+                    *count = count.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                    Ok(())
+                })?;
+            }
+
+            let generation = Self::generation_of(kitty_id_1)
+                .max(Self::generation_of(kitty_id_2))
+                .checked_add(1)
+                .ok_or(ArithmeticError::Overflow)?;
+
+            let mut payload = Self::random_payload(sender)?;
+            // Pairs of a batch share the seed, mixing in the index keeps their offspring apart
+            if let Some(index) = batch_index {
+                index.encode_to(&mut payload);
+            }
+            let selector = Self::breeding_selector(&payload, 0);
+
+            // Mint as much of the litter as the owner and the supply have room for, but always
+            // try at least one so a full owner still gets `TooManyKitties`
+            let litter_size = Self::litter_size(&selector);
+            let owner_room = T::MaxKittiesOwned::get().saturating_sub(Self::owned_count(sender));
+            let supply_room = T::MaxSupply::get().saturating_sub(Self::next_kitty_id());
+            let born = litter_size.min(owner_room).min(supply_room).max(1);
+
+            let kitty_id = Self::breed_offspring(
+                sender,
+                (kitty_id_1, &kitty1),
+                (kitty_id_2, &kitty2),
+                generation,
+                &selector,
+            )?;
+            for litter_index in 1..born {
+                Self::breed_offspring(
+                    sender,
+                    (kitty_id_1, &kitty1),
+                    (kitty_id_2, &kitty2),
+                    generation,
+                    &Self::breeding_selector(&payload, litter_index),
+                )?;
+            }
+
+            if litter_size > 1 {
+                Self::deposit_event(Event::LitterBorn(kitty_id_1, kitty_id_2, born, litter_size));
+            }
+
+            Ok(kitty_id)
+        }
+
+        /// Check everything but ownership and genders that keeps `parent1` and `parent2` from
+        /// breeding, without touching storage.
+        fn ensure_can_breed(
+            parent1: (u32, &Kitty),
+            parent2: (u32, &Kitty),
+        ) -> Result<(), Error<T>> {
+            let (kitty_id_1, kitty1) = parent1;
+            let (kitty_id_2, kitty2) = parent2;
+
+            ensure!(!Self::breeding_paused(), Error::<T>::BreedingPaused);
+            Self::ensure_randomness_ready()?;
+
+            ensure!(
+                !Self::is_dead(kitty_id_1) && !Self::is_dead(kitty_id_2),
+                Error::<T>::KittyDead
+            );
+
+            ensure!(!Self::is_locked(kitty_id_1), Error::<T>::KittyLocked);
+            ensure!(!Self::is_locked(kitty_id_2), Error::<T>::KittyLocked);
+
+            // A reserved kitty only breeds with its partner
+            for (kitty_id, other_id) in &[(kitty_id_1, kitty_id_2), (kitty_id_2, kitty_id_1)] {
+                if let Some(partner_kitty_id) = Self::breeding_partner(*kitty_id) {
+                    ensure!(
+                        partner_kitty_id == *other_id,
+                        Error::<T>::KittyReservedForBreeding
+                    );
+                }
+            }
+
+            ensure!(
+                !Self::is_sterile(kitty_id_1) && !Self::is_sterile(kitty_id_2),
+                Error::<T>::KittySterile
+            );
+
+            ensure!(
+                !T::ForbidSameColorBreeding::get() || kitty1.color() != kitty2.color(),
+                Error::<T>::SameColor
+            );
+
+            ensure!(
+                Self::is_mature(kitty_id_1) && Self::is_mature(kitty_id_2),
+                Error::<T>::KittyTooYoung
+            );
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                Self::is_ready_to_breed(kitty_id_1, now)
+                    && Self::is_ready_to_breed(kitty_id_2, now),
+                Error::<T>::KittyOnCooldown
+            );
+
+            let min_generation = T::MinBreedingGeneration::get();
+            ensure!(
+                Self::generation_of(kitty_id_1) >= min_generation
+                    && Self::generation_of(kitty_id_2) >= min_generation,
+                Error::<T>::GenerationTooLow
+            );
+
+            let max_breedings = T::MaxBreedings::get();
+            ensure!(
+                Self::breed_count(kitty_id_1) < max_breedings
+                    && Self::breed_count(kitty_id_2) < max_breedings,
+                Error::<T>::FertilityExhausted
+            );
+
+            Ok(())
+        }
+
+        /// Mint a single offspring of `parent1` and `parent2` with DNA picked by `selector`.
+        fn breed_offspring(
+            sender: &T::AccountId,
+            parent1: (u32, &Kitty),
+            parent2: (u32, &Kitty),
+            generation: u32,
+            selector: &[u8; 16],
+        ) -> Result<u32, DispatchError> {
+            let (kitty_id_1, kitty1) = parent1;
+            let (kitty_id_2, kitty2) = parent2;
+
+            let mut new_dna = Self::mix_parents(kitty1, kitty2, selector);
+            let mutated_bytes = Self::mutate(&mut new_dna, selector);
+
+            let new_kitty = Kitty::from_bytes(new_dna);
+            let kitty_id = Self::mint(sender, &new_kitty, sender)?;
+            TotalBred::<T>::try_mutate(|total| -> DispatchResult {
+                *total = total.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+
+            KittyParents::<T>::insert(kitty_id, (kitty_id_1, kitty_id_2));
+            KittyGeneration::<T>::insert(kitty_id, generation);
+
+            let gender = new_kitty.gender();
+            Self::deposit_event_with_topics(
+                Event::KittyBred(sender.clone(), kitty_id, new_kitty, gender, generation),
+                kitty_id,
+                &[sender],
+            );
+            if mutated_bytes > 0 {
+                Self::deposit_event(Event::KittyMutated(kitty_id, mutated_bytes));
+            }
+
+            T::OnKittyCreated::on_created(sender, kitty_id);
+
+            Ok(kitty_id)
+        }
+
+        /// Mix the DNA of two parents with `selector`, whichever order they are given in.
+        ///
+        /// The parent with the lower DNA, compared byte by byte, goes into `DnaMixer` first.
+        fn mix_parents(kitty1: &Kitty, kitty2: &Kitty, selector: &[u8; 16]) -> [u8; 16] {
+            let (first, second) = if kitty1.as_bytes() <= kitty2.as_bytes() {
+                (kitty1, kitty2)
+            } else {
+                (kitty2, kitty1)
+            };
+            T::DnaMixer::mix(first.as_bytes(), second.as_bytes(), selector)
+        }
+
+        /// Roll how many offspring a breeding with `selector` produces, from 1 to `MaxLitterSize`.
+        fn litter_size(selector: &[u8; 16]) -> u32 {
+            let max = Self::max_litter_size();
+            #[cfg(feature = "runtime-benchmarks")]
+            if let Some(size) = frame_support::storage::unhashed::get::<u32>(FORCED_LITTER_SIZE) {
+                return size.min(max).max(1);
+            }
+            if max == 1 {
+                return 1;
+            }
+            let entropy = (selector, b"litter").using_encoded(blake2_128);
+            let roll = u32::from_le_bytes([entropy[0], entropy[1], entropy[2], entropy[3]]);
+            1 + roll % max
+        }
+
+        /// `MaxLitterSize`, treating zero as one.
+        pub(crate) fn max_litter_size() -> u32 {
+            T::MaxLitterSize::get().max(1)
+        }
+
+        /// Make every breeding roll a litter of `size`, capped by `MaxLitterSize`, so benchmarks
+        /// can measure the worst case.
+        #[cfg(feature = "runtime-benchmarks")]
+        pub(crate) fn force_litter_size(size: u32) {
+            frame_support::storage::unhashed::put(FORCED_LITTER_SIZE, &size);
+        }
+
+        /// Parents must have different effective genders to breed.
+        fn ensure_opposite_genders(
+            parent1: (u32, &Kitty),
+            parent2: (u32, &Kitty),
+        ) -> DispatchResult {
+            ensure!(
+                Self::resolve_gender(parent1.0, parent1.1)
+                    == Self::resolve_gender(parent2.0, parent2.1).opposite(),
+                Error::<T>::SameGender
+            );
+            Ok(())
+        }
+
+        /// Replace each byte of `dna` by a random byte with a chance of `MutationRate` in 256.
+        ///
+        /// Returns how many bytes were replaced.
+        fn mutate(dna: &mut [u8; 16], selector: &[u8; 16]) -> u8 {
+            let rate = T::MutationRate::get();
+
+            // The first half decides which bytes mutate, the second half provides the new values
+            let entropy = selector.using_encoded(blake2_256);
+            let (rolls, fresh) = entropy.split_at(16);
+
+            let mut mutated_bytes = 0;
+            for (i, byte) in dna.iter_mut().enumerate() {
+                if rolls[i] < rate {
+                    *byte = fresh[i];
+                    mutated_bytes += 1;
+                }
+            }
+            mutated_bytes
+        }
+
+        /// Move a kitty owned by `from` to `to`, together with its name. Any listing is removed.
+        pub(crate) fn do_transfer(
+            from: &T::AccountId,
+            to: &T::AccountId,
+            kitty_id: u32,
+        ) -> DispatchResult {
+            Self::move_kitty(from, to, kitty_id)?;
+
+            Self::deposit_event_with_topics(
+                Event::KittyTransferred(from.clone(), to.clone(), kitty_id),
+                kitty_id,
+                &[from, to],
+            );
+
+            Ok(())
+        }
+
+        /// Deposit `event` with the hashes of `kitty_id` and of each of `accounts` as topics, so
+        /// clients can look it up by kitty or by account.
+        pub(crate) fn deposit_event_with_topics(
+            event: Event<T>,
+            kitty_id: u32,
+            accounts: &[&T::AccountId],
+        ) {
+            let mut topics = Vec::with_capacity(accounts.len() + 1);
+            topics.push(T::Hashing::hash_of(&kitty_id));
+            topics.extend(accounts.iter().map(|account| T::Hashing::hash_of(account)));
+
+            let event = <T as Config>::Event::from(event);
+            <frame_system::Pallet<T>>::deposit_event_indexed(&topics, event.into());
+        }
+
+        /// Like `do_transfer`, without emitting an event.
+        fn move_kitty(from: &T::AccountId, to: &T::AccountId, kitty_id: u32) -> DispatchResult {
+            let kitty = Self::owned_kitty(from, kitty_id)?;
+            ensure!(!Self::is_dead(kitty_id), Error::<T>::KittyDead);
+            ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+            Self::ensure_not_reserved(kitty_id)?;
+
+            Self::add_to_owner(to, kitty_id)?;
+            Self::remove_from_owner(from, kitty_id)?;
+
+            Self::reassign_kitty(from, to, kitty_id, &kitty);
+
+            Ok(())
+        }
+
+        /// Hand `kitty_id` over from `from` to `to` once the owned lists have been updated.
+        fn reassign_kitty(from: &T::AccountId, to: &T::AccountId, kitty_id: u32, kitty: &Kitty) {
+            OwnerOf::<T>::insert(kitty_id, to);
+            Self::record_owner(kitty_id, to);
+            OwnerDna::<T>::remove(from, kitty.as_bytes());
+            OwnerDna::<T>::insert(to, kitty.as_bytes(), ());
+            Approvals::<T>::remove(kitty_id);
+            KittyPrices::<T>::remove(from, kitty_id);
+            ListingExpiry::<T>::remove(from, kitty_id);
+            BreedingApprovals::<T>::remove(from, kitty_id);
+            SwapApprovals::<T>::remove(from, kitty_id);
+            Self::release_breeding_lock(kitty_id);
+
+            if let Some(name) = KittyNames::<T>::take(from, kitty_id) {
+                KittyNames::<T>::insert(to, kitty_id, name);
+            }
+        }
+
+        /// Emit `KittyIdThresholdReached` the first time `next_id` reaches `IdWarningThreshold`.
+        pub(crate) fn check_id_threshold(next_id: u32) {
+            if next_id >= T::IdWarningThreshold::get() && !IdThresholdReached::<T>::get() {
+                IdThresholdReached::<T>::put(true);
+                Self::deposit_event(Event::KittyIdThresholdReached(next_id));
+            }
+        }
+
+        /// The gender of `kitty_id`, honouring admin overrides.
+        pub fn gender_of(kitty_id: u32) -> Option<KittyGender> {
+            Self::kitties(kitty_id).map(|kitty| Self::resolve_gender(kitty_id, &kitty))
+        }
+
+        /// The admin override for `kitty_id` if there is one, otherwise the gender in its DNA.
+        fn resolve_gender(kitty_id: u32, kitty: &Kitty) -> KittyGender {
+            Self::gender_override(kitty_id).unwrap_or_else(|| kitty.gender())
+        }
+
+        /// The per-owner index of `kitty_id`, if it is owned by `owner`.
+        ///
+        /// Kitty ids are global, this index counts the kitties an owner received from zero.
+        pub fn owner_index_of(owner: &T::AccountId, kitty_id: u32) -> Option<u32> {
+            OwnerIndex::<T>::get(owner, kitty_id)
+        }
+
+        /// Count one more living kitty of `gender`.
+        fn increase_gender_count(gender: KittyGender) -> DispatchResult {
+            let increase = |count: &mut u64| -> DispatchResult {
+                *count = count.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            };
+            match gender {
+                KittyGender::Male => MaleCount::<T>::try_mutate(increase),
+                KittyGender::Female => FemaleCount::<T>::try_mutate(increase),
+            }
+        }
+
+        /// Count one less living kitty of `gender`.
+        fn decrease_gender_count(gender: KittyGender) -> DispatchResult {
+            let decrease = |count: &mut u64| -> DispatchResult {
+                *count = count.checked_sub(1).ok_or(ArithmeticError::Underflow)?;
+                Ok(())
+            };
+            match gender {
+                KittyGender::Male => MaleCount::<T>::try_mutate(decrease),
+                KittyGender::Female => FemaleCount::<T>::try_mutate(decrease),
+            }
+        }
+
+        /// Append `owner` to the history of `kitty_id`, dropping the oldest owner when full.
+        fn record_owner(kitty_id: u32, owner: &T::AccountId) {
+            KittyHistory::<T>::mutate(kitty_id, |history| {
+                if history.len() as u32 >= T::MaxHistory::get() && !history.is_empty() {
+                    history.remove(0);
+                }
+                // Only fails if `MaxHistory` is zero, in which case nothing is recorded
+                let _ = history.try_push(owner.clone());
+            });
+        }
+
+        /// Whether `kitty_id` is locked against transfers, burning and breeding.
+        pub fn is_locked(kitty_id: u32) -> bool {
+            LockedKitties::<T>::contains_key(kitty_id)
+        }
+
+        /// Whether `kitty_id` was burned.
+        pub fn is_dead(kitty_id: u32) -> bool {
+            DeadKitties::<T>::contains_key(kitty_id)
+        }
+
+        /// Whether `kitty_id` was made unable to breed.
+        pub fn is_sterile(kitty_id: u32) -> bool {
+            SterileKitties::<T>::contains_key(kitty_id)
+        }
+
+        /// Whether `kitty_id` lived for at least `MaturityPeriod` blocks. Kitties without a birth
+        /// block count as mature.
+        pub fn is_mature(kitty_id: u32) -> bool {
+            Self::birth_block(kitty_id).map_or(true, |born| {
+                <frame_system::Pallet<T>>::block_number().saturating_sub(born)
+                    >= T::MaturityPeriod::get()
+            })
+        }
+
+        /// Lock `kitty_id` until `unlock_kitty` is called for it.
+        pub(crate) fn lock_kitty(kitty_id: u32) {
+            LockedKitties::<T>::insert(kitty_id, ());
+        }
+
+        /// Release a lock taken with `lock_kitty`.
+        pub(crate) fn unlock_kitty(kitty_id: u32) {
+            LockedKitties::<T>::remove(kitty_id);
+        }
+
+        /// Keep `kitty_id` for breeding with `partner_kitty_id` up to and including `until_block`.
+        ///
+        /// The reservation isn't released at `until_block`, it just stops counting after it.
+        pub(crate) fn lock_for_breeding(
+            kitty_id: u32,
+            partner_kitty_id: u32,
+            until_block: T::BlockNumber,
+        ) {
+            BreedingLocks::<T>::insert(kitty_id, (partner_kitty_id, until_block));
+        }
+
+        /// Drop the name of `kitty_id` owned by `owner`, freeing it for other kitties.
+        fn remove_name(owner: &T::AccountId, kitty_id: u32) {
+            if let Some(name) = KittyNames::<T>::take(owner, kitty_id) {
+                if NameOwner::<T>::get(&name) == Some(kitty_id) {
+                    NameOwner::<T>::remove(&name);
+                }
+            }
+        }
+
+        /// Drop the breeding reservation of `kitty_id`, if any, and return its deposit.
+        fn release_breeding_lock(kitty_id: u32) {
+            BreedingLocks::<T>::remove(kitty_id);
+            if let Some((depositor, deposit)) = BreedingDeposits::<T>::take(kitty_id) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+        }
+
+        /// Slash the breeding deposit of `kitty_id`, if any, instead of returning it.
+        pub(crate) fn slash_breeding_deposit(kitty_id: u32) {
+            if let Some((depositor, deposit)) = BreedingDeposits::<T>::take(kitty_id) {
+                let (_, unslashed) = T::Currency::slash_reserved(&depositor, deposit);
+                Self::deposit_event(Event::BreedingDepositSlashed(
+                    depositor,
+                    deposit.saturating_sub(unslashed),
+                ));
+            }
+        }
+
+        /// The kitty `kitty_id` is reserved to breed with, if the reservation still holds.
+        pub fn breeding_partner(kitty_id: u32) -> Option<u32> {
+            let (partner_kitty_id, until_block) = Self::breeding_locks(kitty_id)?;
+            if <frame_system::Pallet<T>>::block_number() <= until_block {
+                Some(partner_kitty_id)
+            } else {
+                None
+            }
+        }
+
+        /// Fail while the whole pallet is paused.
+        pub(crate) fn ensure_not_paused() -> DispatchResult {
+            ensure!(!Self::paused(), Error::<T>::PalletPaused);
+            Ok(())
+        }
+
+        /// Fail before `MinBlockForCreation`, while the randomness source is still predictable.
+        fn ensure_randomness_ready() -> Result<(), Error<T>> {
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= T::MinBlockForCreation::get(),
+                Error::<T>::RandomnessNotReady
+            );
+            Ok(())
+        }
+
+        /// Take `BreedFee` from `who`, burning `BurnRatio` of it and paying the rest to
+        /// `FeeCollector`.
+        fn charge_breed_fee(who: &T::AccountId) -> DispatchResult {
+            let fee = T::BreedFee::get();
+            if fee.is_zero() {
+                return Ok(());
+            }
+
+            let imbalance = T::Currency::withdraw(
+                who,
+                fee,
+                WithdrawReasons::FEE,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| Error::<T>::InsufficientBalance)?;
+            // Splitting the imbalance keeps burned + to_treasury == fee whatever the rounding
+            let (burn, to_treasury) = imbalance.split(T::BurnRatio::get().mul_floor(fee));
+            let burned = burn.peek();
+            let treasury_share = to_treasury.peek();
+            T::Currency::resolve_creating(&T::FeeCollector::get(), to_treasury);
+            // Dropping the imbalance takes it out of the total issuance
+            drop(burn);
+
+            Self::deposit_event(Event::BreedFeeCollected(burned, treasury_share));
+            Ok(())
+        }
+
+        /// Fail unless `who` holds at least `MinCreatorBalance`.
+        fn ensure_creator_balance(who: &T::AccountId) -> DispatchResult {
+            ensure!(
+                T::Currency::free_balance(who) >= T::MinCreatorBalance::get(),
+                Error::<T>::InsufficientBalance
+            );
+            Ok(())
+        }
+
+        /// Fail while `kitty_id` is reserved for breeding.
+        fn ensure_not_reserved(kitty_id: u32) -> DispatchResult {
+            ensure!(
+                Self::breeding_partner(kitty_id).is_none(),
+                Error::<T>::KittyReservedForBreeding
+            );
+            Ok(())
+        }
+
+        /// Whether the breeding cooldown of `kitty_id` has expired at block `now`.
+        fn is_ready_to_breed(kitty_id: u32, now: T::BlockNumber) -> bool {
+            Self::cooldown_of(kitty_id).map_or(true, |until| until <= now)
+        }
+
+        /// Look up `kitty_id`, making sure it is owned by `owner`.
+        fn owned_kitty(owner: &T::AccountId, kitty_id: u32) -> Result<Kitty, DispatchError> {
+            ensure!(
+                Self::owner_of(kitty_id).as_ref() == Some(owner),
+                Error::<T>::InvalidKittyId
+            );
+            Self::kitties(kitty_id).ok_or_else(|| Error::<T>::InvalidKittyId.into())
+        }
+
+        /// Reserve `KittyDeposit` from `owner` for `kitty_id` and remember how much was locked.
+        fn reserve_deposit(owner: &T::AccountId, kitty_id: u32) -> DispatchResult {
+            let deposit = T::KittyDeposit::get();
+            T::Currency::reserve(owner, deposit).map_err(|_| Error::<T>::InsufficientBalance)?;
+            KittyDeposits::<T>::insert(kitty_id, (owner.clone(), deposit));
+            Ok(())
+        }
+
+        /// Record `kitty_id` as owned by `owner`, respecting `MaxKittiesOwned`.
+        fn add_to_owner(owner: &T::AccountId, kitty_id: u32) -> DispatchResult {
+            OwnedCount::<T>::try_mutate(owner, |count| -> DispatchResult {
+                let new_count = count.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                ensure!(
+                    new_count <= T::MaxKittiesOwned::get(),
+                    Error::<T>::TooManyKitties
+                );
+                *count = new_count;
+                Ok(())
+            })?;
+            KittiesOwned::<T>::try_mutate(owner, |ids| -> DispatchResult {
+                ids.try_push(kitty_id)
+                    .map_err(|_| Error::<T>::TooManyKitties)?;
+                Ok(())
+            })?;
+            OwnerNextIndex::<T>::try_mutate(owner, |next_index| -> DispatchResult {
+                OwnerIndex::<T>::insert(owner, kitty_id, *next_index);
+                *next_index = next_index.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })
+        }
+
+        /// Remove `kitty_id` from the kitties owned by `owner`.
+        fn remove_from_owner(owner: &T::AccountId, kitty_id: u32) -> DispatchResult {
+            OwnedCount::<T>::try_mutate(owner, |count| -> DispatchResult {
+                *count = count.checked_sub(1).ok_or(ArithmeticError::Underflow)?;
+                Ok(())
+            })?;
+            KittiesOwned::<T>::try_mutate(owner, |ids| -> DispatchResult {
+                let index = ids
+                    .iter()
+                    .position(|id| *id == kitty_id)
+                    .ok_or(Error::<T>::InvalidKittyId)?;
+                ids.swap_remove(index);
+                Ok(())
+            })?;
+            OwnerIndex::<T>::remove(owner, kitty_id);
+            Ok(())
         }
     }
 }
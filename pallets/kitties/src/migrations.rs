@@ -0,0 +1,103 @@
+//! Storage migrations for pallet-kitties.
+
+use super::*;
+use frame_support::{
+    storage::{migration::StorageIterator, with_transaction},
+    traits::{GetStorageVersion, PalletInfoAccess},
+    weights::Weight,
+    ReversibleStorageHasher,
+};
+use sp_runtime::TransactionOutcome;
+
+/// Run every migration step the on-chain storage has not seen yet, oldest first.
+///
+/// Each step checks the on-chain storage version itself and bumps it when done, so calling this
+/// on up to date storage only costs the version reads.
+pub fn migrate<T: Config>() -> Weight {
+    let mut weight = T::DbWeight::get().reads(1);
+    weight = weight.saturating_add(v1::migrate::<T>());
+    weight
+}
+
+/// Version 1 keys `Kitties` by kitty id only and tracks ownership in `OwnerOf`.
+pub mod v1 {
+    use super::*;
+
+    /// Move every `(owner, kitty_id) => kitty` entry of the old `Kitties` double map into
+    /// `Kitties` and `OwnerOf`, rebuilding the owner, count and DNA indexes and moving
+    /// `NextKittyId` past the highest id.
+    ///
+    /// Migrated kitties carry no deposit. Owners had no cap before, so a kitty that doesn't fit
+    /// into `MaxKittiesOwned` of its owner is left abandoned, free for anyone to claim.
+    pub fn migrate<T: Config>() -> Weight {
+        let on_chain_version = Pallet::<T>::on_chain_storage_version();
+        if on_chain_version >= 1 {
+            return 0;
+        }
+
+        let pallet_prefix = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+        // Collect first: the new entries are written under the same storage prefix.
+        let old_kitties: Vec<(Vec<u8>, Kitty)> =
+            StorageIterator::<Kitty>::new(pallet_prefix, b"Kitties")
+                .drain()
+                .collect();
+
+        let mut migrated: Weight = 0;
+        let mut next_kitty_id = Pallet::<T>::next_kitty_id();
+        for (key, kitty) in old_kitties {
+            if let Some((owner, kitty_id)) = decode_old_key::<T>(&key) {
+                let indexed =
+                    with_transaction(
+                        || match Pallet::<T>::index_kitty(&owner, kitty_id, &kitty) {
+                            Ok(()) => TransactionOutcome::Commit(Ok(())),
+                            Err(error) => TransactionOutcome::Rollback(Err(error)),
+                        },
+                    );
+                match indexed {
+                    Ok(()) => {
+                        Kitties::<T>::insert(kitty_id, kitty);
+                        OwnerOf::<T>::insert(kitty_id, owner);
+                    }
+                    Err(error) => {
+                        log::warn!(
+                            target: "runtime::kitties",
+                            "abandoning migrated kitty {}: {:?}",
+                            kitty_id,
+                            error
+                        );
+                        DnaExists::<T>::insert(kitty.as_bytes(), ());
+                        AbandonedKitties::<T>::insert(kitty_id, kitty);
+                    }
+                }
+                next_kitty_id = next_kitty_id.max(kitty_id.saturating_add(1));
+                migrated += 1;
+            }
+        }
+        NextKittyId::<T>::put(next_kitty_id);
+
+        StorageVersion::new(1).put::<Pallet<T>>();
+
+        T::DbWeight::get().reads_writes(migrated * 7 + 2, migrated * 13 + 2)
+    }
+
+    /// The old key is `blake2_128_concat(owner) ++ blake2_128_concat(kitty_id)`.
+    fn decode_old_key<T: Config>(key: &[u8]) -> Option<(T::AccountId, u32)> {
+        let mut key = reverse_blake2_128_concat(key)?;
+        let owner = T::AccountId::decode(&mut key).ok()?;
+        let mut key = reverse_blake2_128_concat(key)?;
+        let kitty_id = u32::decode(&mut key).ok()?;
+        if key.is_empty() {
+            Some((owner, kitty_id))
+        } else {
+            None
+        }
+    }
+
+    /// Strip the hash part of a `Blake2_128Concat` key, `None` if the key is too short.
+    fn reverse_blake2_128_concat(key: &[u8]) -> Option<&[u8]> {
+        if key.len() < 16 {
+            return None;
+        }
+        Some(Blake2_128Concat::reverse(key))
+    }
+}
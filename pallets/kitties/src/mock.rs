@@ -0,0 +1,392 @@
+use crate as pallet_kitties;
+use codec::Encode;
+use frame_support::{
+    ord_parameter_types, parameter_types,
+    traits::{GenesisBuild, Get, OnFinalize, OnInitialize, Randomness},
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Permill,
+};
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const FEE_COLLECTOR: u64 = 4;
+
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+pub const KITTY_DEPOSIT: u64 = 10;
+pub const BREEDING_DEPOSIT: u64 = 20;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>, Config<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+/// Declare `Get` parameters backed by thread locals, so single tests can tweak them with
+/// `set` while `reset_parameters` restores the defaults.
+macro_rules! thread_local_parameters {
+    ($($name:ident: $type:ty = $default:expr;)*) => {
+        $(
+            pub struct $name;
+
+            impl $name {
+                fn value() -> &'static std::thread::LocalKey<RefCell<$type>> {
+                    thread_local! {
+                        static VALUE: RefCell<$type> = RefCell::new($default);
+                    }
+                    &VALUE
+                }
+
+                pub fn set(value: $type) {
+                    Self::value().with(|v| *v.borrow_mut() = value);
+                }
+            }
+
+            impl Get<$type> for $name {
+                fn get() -> $type {
+                    Self::value().with(|v| *v.borrow())
+                }
+            }
+        )*
+
+        fn reset_parameters() {
+            $($name::set($default);)*
+        }
+    };
+}
+
+thread_local! {
+    static RANDOM_SEED: RefCell<H256> = RefCell::new(H256::repeat_byte(1));
+    static RANDOM_CALLS: RefCell<u32> = RefCell::new(0);
+}
+
+/// Deterministic randomness: the first call after `set_random_seed` returns the seed itself,
+/// later calls return values derived from the seed so repeated mints get distinct DNA.
+pub struct MockRandomness;
+impl Randomness<H256, u64> for MockRandomness {
+    fn random(_subject: &[u8]) -> (H256, u64) {
+        let seed = RANDOM_SEED.with(|v| *v.borrow());
+        let calls = RANDOM_CALLS.with(|v| {
+            let calls = *v.borrow();
+            *v.borrow_mut() = calls + 1;
+            calls
+        });
+        let output = if calls == 0 {
+            seed
+        } else {
+            H256::from(blake2_256(&(seed, calls).encode()))
+        };
+        (output, System::block_number())
+    }
+}
+
+pub fn set_random_seed(seed: H256) {
+    RANDOM_SEED.with(|v| *v.borrow_mut() = seed);
+    RANDOM_CALLS.with(|v| *v.borrow_mut() = 0);
+}
+
+thread_local! {
+    static CREATED_KITTIES: RefCell<Vec<(u64, u32)>> = RefCell::new(Vec::new());
+}
+
+/// Records every `OnKittyCreated` call, read them back with `created_kitties`.
+pub struct RecordCreatedKitties;
+impl pallet_kitties::OnKittyCreated<u64> for RecordCreatedKitties {
+    fn on_created(owner: &u64, kitty_id: u32) {
+        CREATED_KITTIES.with(|v| v.borrow_mut().push((*owner, kitty_id)));
+    }
+}
+
+pub fn created_kitties() -> Vec<(u64, u32)> {
+    CREATED_KITTIES.with(|v| v.borrow().clone())
+}
+
+thread_local_parameters! {
+    MaxKittiesOwned: u32 = 10;
+    IdWarningThreshold: u32 = u32::MAX;
+    BreedCooldownPeriod: u64 = 0;
+    MutationRate: u8 = 0;
+    MaxSupply: u32 = u32::MAX;
+    MaxLitterSize: u32 = 1;
+    MinBreedingGeneration: u32 = 0;
+    MaturityPeriod: u64 = 0;
+    MaxBreedings: u32 = u32::MAX;
+    MarketplaceFee: Permill = Permill::zero();
+    MinCreatorBalance: u64 = 0;
+    RenameFee: u64 = 0;
+    UniqueNames: bool = false;
+    BreedFee: u64 = 0;
+    BurnRatio: Permill = Permill::zero();
+    MinBlockForCreation: u64 = 0;
+    ForbidSameColorBreeding: bool = false;
+}
+
+parameter_types! {
+    pub const MaxNameLength: u32 = 8;
+    pub const MaxKeyLen: u32 = 10;
+    pub const MaxValueLen: u32 = 6;
+    pub const MaxAttributes: u32 = 2;
+    pub const KittyDeposit: u64 = KITTY_DEPOSIT;
+    pub const BreedingDeposit: u64 = BREEDING_DEPOSIT;
+    pub const MaxBatchSize: u32 = 5;
+    pub const GraveyardRetention: u64 = 10;
+    pub const MaxHistory: u32 = 3;
+    pub const OcwInterval: u32 = 5;
+    pub const FeeCollector: u64 = FEE_COLLECTOR;
+    pub const MaxExpiringPerBlock: u32 = 2;
+}
+
+ord_parameter_types! {
+    pub const Gifter: u64 = CHARLIE;
+}
+
+impl pallet_kitties::Config for Test {
+    type Event = Event;
+    type Randomness = MockRandomness;
+    type MaxNameLength = MaxNameLength;
+    type MaxKeyLen = MaxKeyLen;
+    type MaxValueLen = MaxValueLen;
+    type MaxAttributes = MaxAttributes;
+    type MaxKittiesOwned = MaxKittiesOwned;
+    type Currency = Balances;
+    type KittyDeposit = KittyDeposit;
+    type BreedingDeposit = BreedingDeposit;
+    type MinCreatorBalance = MinCreatorBalance;
+    type MinBlockForCreation = MinBlockForCreation;
+    type WeightInfo = ();
+    type IdWarningThreshold = IdWarningThreshold;
+    type BreedCooldownPeriod = BreedCooldownPeriod;
+    type MaxBatchSize = MaxBatchSize;
+    type GraveyardRetention = GraveyardRetention;
+    type AdminOrigin = system::EnsureRoot<u64>;
+    type DnaMixer = pallet_kitties::BitmaskMixer;
+    type MutationRate = MutationRate;
+    type OnKittyCreated = RecordCreatedKitties;
+    type PauseOrigin = system::EnsureRoot<u64>;
+    type ForceOrigin = system::EnsureRoot<u64>;
+    type MaxHistory = MaxHistory;
+    type MaxSupply = MaxSupply;
+    type OcwInterval = OcwInterval;
+    type GiftOrigin = system::EnsureSignedBy<Gifter, u64>;
+    type MaxLitterSize = MaxLitterSize;
+    type ForbidSameColorBreeding = ForbidSameColorBreeding;
+    type MaturityPeriod = MaturityPeriod;
+    type MaxBreedings = MaxBreedings;
+    type MinBreedingGeneration = MinBreedingGeneration;
+    type MarketplaceFee = MarketplaceFee;
+    type FeeCollector = FeeCollector;
+    type UniqueNames = UniqueNames;
+    type RenameFee = RenameFee;
+    type BreedFee = BreedFee;
+    type BurnRatio = BurnRatio;
+    type MaxExpiringPerBlock = MaxExpiringPerBlock;
+}
+
+pub struct ExtBuilder {
+    kitties: Vec<(u64, [u8; 16])>,
+}
+
+impl Default for ExtBuilder {
+    fn default() -> Self {
+        reset_parameters();
+        set_random_seed(H256::repeat_byte(1));
+        CREATED_KITTIES.with(|v| v.borrow_mut().clear());
+        Self {
+            kitties: Vec::new(),
+        }
+    }
+}
+
+impl ExtBuilder {
+    pub fn genesis_kitties(mut self, kitties: Vec<(u64, [u8; 16])>) -> Self {
+        self.kitties = kitties;
+        self
+    }
+
+    pub fn max_kitties_owned(self, max_kitties_owned: u32) -> Self {
+        MaxKittiesOwned::set(max_kitties_owned);
+        self
+    }
+
+    pub fn id_warning_threshold(self, id_warning_threshold: u32) -> Self {
+        IdWarningThreshold::set(id_warning_threshold);
+        self
+    }
+
+    pub fn breed_cooldown_period(self, breed_cooldown_period: u64) -> Self {
+        BreedCooldownPeriod::set(breed_cooldown_period);
+        self
+    }
+
+    pub fn mutation_rate(self, mutation_rate: u8) -> Self {
+        MutationRate::set(mutation_rate);
+        self
+    }
+
+    pub fn max_supply(self, max_supply: u32) -> Self {
+        MaxSupply::set(max_supply);
+        self
+    }
+
+    pub fn max_litter_size(self, max_litter_size: u32) -> Self {
+        MaxLitterSize::set(max_litter_size);
+        self
+    }
+
+    pub fn min_breeding_generation(self, min_breeding_generation: u32) -> Self {
+        MinBreedingGeneration::set(min_breeding_generation);
+        self
+    }
+
+    pub fn maturity_period(self, maturity_period: u64) -> Self {
+        MaturityPeriod::set(maturity_period);
+        self
+    }
+
+    pub fn max_breedings(self, max_breedings: u32) -> Self {
+        MaxBreedings::set(max_breedings);
+        self
+    }
+
+    pub fn marketplace_fee(self, marketplace_fee: Permill) -> Self {
+        MarketplaceFee::set(marketplace_fee);
+        self
+    }
+
+    pub fn min_creator_balance(self, min_creator_balance: u64) -> Self {
+        MinCreatorBalance::set(min_creator_balance);
+        self
+    }
+
+    pub fn rename_fee(self, rename_fee: u64) -> Self {
+        RenameFee::set(rename_fee);
+        self
+    }
+
+    pub fn unique_names(self, unique_names: bool) -> Self {
+        UniqueNames::set(unique_names);
+        self
+    }
+
+    pub fn breed_fee(self, breed_fee: u64) -> Self {
+        BreedFee::set(breed_fee);
+        self
+    }
+
+    pub fn burn_ratio(self, burn_ratio: Permill) -> Self {
+        BurnRatio::set(burn_ratio);
+        self
+    }
+
+    pub fn min_block_for_creation(self, min_block_for_creation: u64) -> Self {
+        MinBlockForCreation::set(min_block_for_creation);
+        self
+    }
+
+    pub fn forbid_same_color_breeding(self, forbid_same_color_breeding: bool) -> Self {
+        ForbidSameColorBreeding::set(forbid_same_color_breeding);
+        self
+    }
+
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut t = system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![
+                (ALICE, INITIAL_BALANCE),
+                (BOB, INITIAL_BALANCE),
+                (CHARLIE, INITIAL_BALANCE),
+            ],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        pallet_kitties::GenesisConfig::<Test> {
+            kitties: self.kitties,
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    ExtBuilder::default().build()
+}
+
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::on_finalize(System::block_number());
+        System::set_block_number(System::block_number() + 1);
+        System::on_initialize(System::block_number());
+        KittiesModule::on_initialize(System::block_number());
+    }
+}
@@ -0,0 +1,54 @@
+//! Runtime API for reading a kitty together with its metadata in a single call.
+
+use crate::{Kitty, KittyInfo};
+use codec::Codec;
+use sp_runtime::DispatchError;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait KittiesApi<AccountId, BlockNumber, Balance>
+    where
+        AccountId: Codec,
+        BlockNumber: Codec,
+        Balance: Codec,
+    {
+        /// Return the kitty `kitty_id` if it is owned by `owner`.
+        fn kitty_info(owner: AccountId, kitty_id: u32) -> Option<KittyInfo<BlockNumber>>;
+
+        /// Return up to `limit` kitties of `owner` starting at `start_key`, plus the next cursor.
+        fn kitties_paged(
+            owner: AccountId,
+            start_key: Option<u32>,
+            limit: u32,
+        ) -> (Vec<(u32, Kitty)>, Option<u32>);
+
+        /// Return a UTF-8 summary of the kitty `kitty_id` if it is owned by `owner`.
+        fn describe_kitty(owner: AccountId, kitty_id: u32) -> Option<Vec<u8>>;
+
+        /// Return the number of male and female kitties of `owner`.
+        fn owner_gender_ratio(owner: AccountId) -> (u32, u32);
+
+        /// Return the number of kitties `owner` holds.
+        fn owned_count(owner: AccountId) -> u32;
+
+        /// Return the number of DNA bits the kitties `kitty_id_1` and `kitty_id_2` differ in,
+        /// `None` if either doesn't exist.
+        fn breeding_compatibility(kitty_id_1: u32, kitty_id_2: u32) -> Option<u32>;
+
+        /// Return up to `limit` ids of existing kitties in ascending order, from `start` on.
+        fn all_kitty_ids(start: u32, limit: u32) -> Vec<u32>;
+
+        /// Return up to `limit` listed kitties as (kitty_id, seller, price) in ascending id
+        /// order, from `start` on.
+        fn listed_kitties(start: u32, limit: u32) -> Vec<(u32, AccountId, Balance)>;
+
+        /// Return the kitty `owner` would get from breeding `kitty_id_1` with `kitty_id_2` if
+        /// the randomness produced `selector`. A real breeding draws fresh randomness.
+        fn simulate_breed(
+            owner: AccountId,
+            kitty_id_1: u32,
+            kitty_id_2: u32,
+            selector: [u8; 16],
+        ) -> Result<Kitty, DispatchError>;
+    }
+}
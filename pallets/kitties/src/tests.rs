@@ -0,0 +1,3421 @@
+use crate::{mock::*, *};
+use frame_support::traits::OffchainWorker;
+use frame_support::{
+    assert_noop, assert_ok,
+    weights::{Pays, Weight, WithPostDispatchInfo},
+};
+use sp_core::{
+    offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+    H256,
+};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn last_event() -> mock::Event {
+    System::events().pop().expect("event expected").event
+}
+
+/// The topics `event` was deposited with.
+fn topics_of(event: crate::Event<Test>) -> Vec<H256> {
+    let event = mock::Event::from(event);
+    System::events()
+        .into_iter()
+        .find(|record| record.event == event)
+        .expect("event expected")
+        .topics
+}
+
+/// Store a kitty with the given DNA directly, bypassing randomness.
+fn insert_kitty(owner: u64, dna: [u8; 16]) -> u32 {
+    let kitty_id = KittiesModule::next_kitty_id();
+    Kitties::<Test>::insert(kitty_id, Kitty(dna));
+    OwnerOf::<Test>::insert(kitty_id, owner);
+    NextKittyId::<Test>::put(kitty_id + 1);
+    KittiesOwned::<Test>::mutate(owner, |ids| ids.try_push(kitty_id).expect("below cap"));
+    OwnedCount::<Test>::mutate(owner, |count| *count += 1);
+    KittyCount::<Test>::mutate(|count| *count += 1);
+    match KittyGender::from_dna(&dna) {
+        KittyGender::Male => MaleCount::<Test>::mutate(|count| *count += 1),
+        KittyGender::Female => FemaleCount::<Test>::mutate(|count| *count += 1),
+    }
+    kitty_id
+}
+
+fn bounded_name(name: &[u8]) -> BoundedVec<u8, MaxNameLength> {
+    name.to_vec().try_into().expect("name fits")
+}
+
+fn dna_with_step(step: u8) -> [u8; 16] {
+    let mut dna = [0u8; 16];
+    for (i, byte) in dna.iter_mut().enumerate() {
+        *byte = (i as u8).wrapping_mul(step);
+    }
+    dna
+}
+
+#[test]
+fn is_male_and_is_female_follow_gender() {
+    let mut dna = [0xaa; 16];
+    for first_byte in &[0x00, 0x02, 0xfe] {
+        dna[0] = *first_byte;
+        let kitty = Kitty(dna);
+        assert!(kitty.is_male());
+        assert!(!kitty.is_female());
+    }
+    for first_byte in &[0x01, 0x03, 0xff] {
+        dna[0] = *first_byte;
+        let kitty = Kitty(dna);
+        assert!(kitty.is_female());
+        assert!(!kitty.is_male());
+    }
+}
+
+#[test]
+fn limits_are_exposed_in_metadata() {
+    use frame_support::metadata::{DecodeDifferent, DefaultByte};
+
+    let constant = |name: &'static str| -> Vec<u8> {
+        let metadata = KittiesModule::module_constants_metadata()
+            .iter()
+            .find(|constant| constant.name == DecodeDifferent::Encode(name))
+            .unwrap_or_else(|| panic!("{} is in the metadata", name));
+        match &metadata.value {
+            DecodeDifferent::Encode(getter) => getter.0.default_byte(),
+            DecodeDifferent::Decoded(value) => value.clone(),
+        }
+    };
+
+    new_test_ext().execute_with(|| {
+        assert_eq!(constant("MaxKittiesOwned"), 10u32.encode());
+        assert_eq!(constant("MaxNameLength"), MaxNameLength::get().encode());
+        assert_eq!(constant("KittyDeposit"), KITTY_DEPOSIT.encode());
+        assert_eq!(constant("BreedCooldownPeriod"), 0u64.encode());
+        assert_eq!(constant("max_supply"), u32::MAX.encode());
+        assert_eq!(
+            constant("version"),
+            env!("CARGO_PKG_VERSION").as_bytes().to_vec().encode()
+        );
+    });
+}
+
+#[test]
+fn traits_are_decoded_from_dna() {
+    // DNA is [0, 10, 20, 30, ...]
+    let kitty = Kitty(dna_with_step(10));
+
+    assert_eq!(
+        kitty.traits(),
+        KittyTraits {
+            body_color: BodyColor::Ginger,
+            eye_shape: EyeShape::Round,
+            pattern: Pattern::Spotted,
+        }
+    );
+}
+
+#[test]
+fn traits_wrap_around_the_variant_count() {
+    let mut dna = [0u8; 16];
+    dna[1] = 15;
+    dna[2] = 255;
+    dna[3] = 9;
+
+    assert_eq!(
+        Kitty(dna).traits(),
+        KittyTraits {
+            body_color: BodyColor::Lilac,
+            eye_shape: EyeShape::Sleepy,
+            pattern: Pattern::Tabby,
+        }
+    );
+}
+
+#[test]
+fn color_matches_body_color() {
+    let mut dna = [0u8; 16];
+    dna[1] = 15;
+    assert_eq!(Kitty(dna).color(), 7);
+    assert_eq!(Kitty(dna_with_step(10)).color(), BodyColor::Ginger as u8);
+}
+
+#[test]
+fn opposite_gender_swaps_the_variant() {
+    assert_eq!(KittyGender::Male.opposite(), KittyGender::Female);
+    assert_eq!(KittyGender::Female.opposite(), KittyGender::Male);
+    assert_eq!(KittyGender::Male.opposite().opposite(), KittyGender::Male);
+}
+
+#[test]
+fn kitty_bytes_and_scale_round_trip() {
+    let dna = dna_with_step(7);
+    let kitty = Kitty::from_bytes(dna);
+    assert_eq!(kitty.as_bytes(), &dna);
+    assert_eq!(
+        Kitty::decode(&mut &kitty.encode()[..]).ok(),
+        Some(kitty.clone())
+    );
+    // A kitty encodes as its bare 16 DNA bytes
+    assert_eq!(kitty.encode(), dna.to_vec());
+
+    assert_eq!(Kitty::from_bytes([2u8; 16]).gender(), KittyGender::Male);
+    assert_eq!(Kitty::from_bytes([3u8; 16]).gender(), KittyGender::Female);
+}
+
+#[test]
+fn gender_follows_first_dna_byte() {
+    let mut dna = [0u8; 16];
+    assert_eq!(KittyGender::from_dna(&dna), KittyGender::Male);
+
+    dna[0] = 1;
+    assert_eq!(KittyGender::from_dna(&dna), KittyGender::Female);
+
+    dna[0] = 254;
+    assert_eq!(Kitty(dna).gender(), KittyGender::Male);
+
+    dna[0] = 255;
+    assert_eq!(Kitty(dna).gender(), KittyGender::Female);
+}
+
+#[test]
+fn rarity_score_counts_rare_bytes() {
+    assert_eq!(Kitty([0x00; 16]).rarity_score(), 0);
+    assert_eq!(Kitty([0xef; 16]).rarity_score(), 0);
+    assert_eq!(Kitty([0xf0; 16]).rarity_score(), 16);
+    assert_eq!(Kitty([0xff; 16]).rarity_score(), 32);
+
+    let mut dna = [0u8; 16];
+    dna[0] = 0xf5;
+    dna[7] = 0xff;
+    dna[15] = 0x80;
+    assert_eq!(Kitty(dna).rarity_score(), 3);
+}
+
+#[test]
+fn dna_distance_counts_differing_bits() {
+    assert_eq!(dna_distance(&Kitty([0x00; 16]), &Kitty([0xff; 16])), 128);
+    assert_eq!(dna_distance(&Kitty([0xa5; 16]), &Kitty([0xa5; 16])), 0);
+
+    let mut dna = [0x00; 16];
+    dna[0] = 0b1000_0001;
+    dna[15] = 0b0000_0100;
+    assert_eq!(dna_distance(&Kitty([0x00; 16]), &Kitty(dna)), 3);
+    assert_eq!(dna_distance(&Kitty(dna), &Kitty([0x00; 16])), 3);
+}
+
+#[test]
+fn breeding_compatibility_needs_both_kitties() {
+    new_test_ext().execute_with(|| {
+        let alices = insert_kitty(ALICE, [0x00; 16]);
+        let bobs = insert_kitty(BOB, [0x0f; 16]);
+
+        assert_eq!(
+            KittiesModule::breeding_compatibility(alices, bobs),
+            Some(64)
+        );
+        assert_eq!(KittiesModule::breeding_compatibility(alices, 42), None);
+        assert_eq!(KittiesModule::breeding_compatibility(42, bobs), None);
+    });
+}
+
+#[test]
+fn describe_kitty_summarizes_gender_generation_and_rarity() {
+    new_test_ext().execute_with(|| {
+        let plain = insert_kitty(ALICE, [0x00; 16]);
+        let mut dna = [0x00; 16];
+        dna[0] = 0xff;
+        dna[1] = 0xf0;
+        dna[2] = 0xf5;
+        let rare = insert_kitty(ALICE, dna);
+        KittyGeneration::<Test>::insert(rare, 3);
+
+        assert_eq!(
+            KittiesModule::describe_kitty(&ALICE, plain),
+            Some(b"Male, Generation 0, Common (score 0)".to_vec())
+        );
+        assert_eq!(
+            KittiesModule::describe_kitty(&ALICE, rare),
+            Some(b"Female, Generation 3, Uncommon (score 4)".to_vec())
+        );
+        assert_eq!(KittiesModule::describe_kitty(&BOB, plain), None);
+
+        let legendary = insert_kitty(BOB, [0xff; 16]);
+        KittyGeneration::<Test>::insert(legendary, 12);
+        assert_eq!(
+            KittiesModule::describe_kitty(&BOB, legendary),
+            Some(b"Female, Generation 12, Legendary (score 32)".to_vec())
+        );
+    });
+}
+
+#[test]
+fn rarest_owned_picks_highest_score() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(KittiesModule::rarest_owned(&ALICE), None);
+
+        insert_kitty(ALICE, [0x00; 16]);
+        let rare = insert_kitty(ALICE, [0xf0; 16]);
+        insert_kitty(ALICE, [0xf0; 16]);
+        insert_kitty(BOB, [0xff; 16]);
+
+        assert_eq!(KittiesModule::rarest_owned(&ALICE), Some(rare));
+    });
+}
+
+#[test]
+fn bitmask_mixer_picks_bits_by_selector() {
+    let parent1 = [0b1010_1010; 16];
+    let parent2 = [0b0101_0101; 16];
+
+    assert_eq!(BitmaskMixer::mix(&parent1, &parent2, &[0xff; 16]), parent1);
+    assert_eq!(BitmaskMixer::mix(&parent1, &parent2, &[0x00; 16]), parent2);
+    assert_eq!(
+        BitmaskMixer::mix(&parent1, &parent2, &[0xf0; 16]),
+        [0b1010_0101; 16]
+    );
+}
+
+#[test]
+fn create_increments_next_kitty_id() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(KittiesModule::next_kitty_id(), 0);
+
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_eq!(KittiesModule::next_kitty_id(), 1);
+        assert!(KittiesModule::kitties(0).is_some());
+        assert_eq!(KittiesModule::owner_of(0), Some(ALICE));
+        assert_eq!(KittiesModule::kitty_count(), 1);
+        assert_eq!(KittiesModule::owned_count(ALICE), 1);
+        assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![0]);
+    });
+}
+
+#[test]
+fn create_and_breed_record_last_created_kitty() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(KittiesModule::last_created_kitty(ALICE), None);
+
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(KittiesModule::last_created_kitty(ALICE), Some(0));
+
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        assert_eq!(KittiesModule::last_created_kitty(ALICE), Some(3));
+        assert_eq!(KittiesModule::last_created_kitty(BOB), None);
+    });
+}
+
+#[test]
+fn owner_index_counts_per_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::create(Origin::signed(BOB)));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_eq!(KittiesModule::owner_index_of(&ALICE, 0), Some(0));
+        assert_eq!(KittiesModule::owner_index_of(&BOB, 1), Some(0));
+        assert_eq!(KittiesModule::owner_index_of(&ALICE, 2), Some(1));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(BOB), ALICE, 1));
+        assert_eq!(KittiesModule::owner_index_of(&BOB, 1), None);
+        assert_eq!(KittiesModule::owner_index_of(&ALICE, 1), Some(2));
+    });
+}
+
+#[test]
+fn owned_count_follows_creates_and_burns() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..3 {
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        }
+        assert_eq!(KittiesModule::owned_count(ALICE), 3);
+
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 1));
+        assert_eq!(KittiesModule::owned_count(ALICE), 2);
+        assert_eq!(KittiesModule::owned_count(BOB), 0);
+    });
+}
+
+#[test]
+fn totals_distinguish_created_and_bred() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+        assert_eq!(KittiesModule::total_created(), 2);
+        assert_eq!(KittiesModule::total_bred(), 1);
+    });
+}
+
+#[test]
+fn on_kitty_created_hook_is_called() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::create_batch(Origin::signed(BOB), 2));
+
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+        assert_eq!(
+            created_kitties(),
+            vec![(ALICE, 0), (BOB, 1), (BOB, 2), (ALICE, 5)]
+        );
+    });
+}
+
+#[test]
+fn create_rejects_duplicate_dna() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 0));
+
+        // Same seed, sender, extrinsic index and nonce give the same DNA again
+        AccountNonce::<Test>::remove(ALICE);
+        set_random_seed(H256::repeat_byte(1));
+        assert_noop!(
+            KittiesModule::create(Origin::signed(ALICE)),
+            Error::<Test>::DuplicateDna
+        );
+
+        // Burning frees the DNA up
+        assert_ok!(KittiesModule::burn(Origin::signed(BOB), 0));
+        AccountNonce::<Test>::remove(ALICE);
+        set_random_seed(H256::repeat_byte(1));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+    });
+}
+
+#[test]
+fn create_rejects_dna_the_sender_already_owns() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        AccountNonce::<Test>::remove(ALICE);
+        set_random_seed(H256::repeat_byte(1));
+        assert_noop!(
+            KittiesModule::create(Origin::signed(ALICE)),
+            Error::<Test>::DuplicateDnaForOwner
+        );
+        assert!(OwnerDna::<Test>::contains_key(
+            ALICE,
+            KittiesModule::kitties(0).unwrap().0
+        ));
+    });
+}
+
+#[test]
+fn gender_counts_follow_mints_overrides_and_burns() {
+    new_test_ext().execute_with(|| {
+        // With these seeds only the fourth kitty gets an even first DNA byte
+        let seeds = [3u8, 4, 9, 12, 21];
+        for seed in seeds.iter() {
+            set_random_seed(H256::repeat_byte(*seed));
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        }
+        assert_eq!(KittiesModule::gender_of(3), Some(KittyGender::Male));
+        assert_eq!(KittiesModule::male_count(), 1);
+        assert_eq!(KittiesModule::female_count(), 4);
+
+        assert_ok!(KittiesModule::admin_set_gender(
+            Origin::root(),
+            3,
+            KittyGender::Female
+        ));
+        assert_eq!(KittiesModule::male_count(), 0);
+        assert_eq!(KittiesModule::female_count(), 5);
+
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 3));
+        assert_eq!(KittiesModule::male_count(), 0);
+        assert_eq!(KittiesModule::female_count(), 4);
+    });
+}
+
+#[test]
+fn create_emits_first_kitty_event_when_owning_none() {
+    new_test_ext().execute_with(|| {
+        let first_kitty =
+            |kitty_id| mock::Event::from(crate::Event::FirstKittyCreated(ALICE, kitty_id));
+        let first_kitty_events = || {
+            System::events()
+                .into_iter()
+                .filter(|record| record.event == first_kitty(0) || record.event == first_kitty(2))
+                .count()
+        };
+
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(last_event(), first_kitty(0));
+
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(first_kitty_events(), 1);
+
+        // Burning every kitty makes the account new again
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 0));
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 1));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(last_event(), first_kitty(2));
+        assert_eq!(first_kitty_events(), 2);
+    });
+}
+
+#[test]
+fn create_reserves_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_eq!(Balances::reserved_balance(ALICE), KITTY_DEPOSIT);
+        assert_eq!(
+            KittiesModule::kitty_deposits(0),
+            Some((ALICE, KITTY_DEPOSIT))
+        );
+    });
+}
+
+#[test]
+fn create_records_birth_block() {
+    new_test_ext().execute_with(|| {
+        run_to_block(5);
+
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_eq!(KittiesModule::birth_block(0), Some(5));
+    });
+}
+
+#[test]
+fn create_requires_min_creator_balance() {
+    ExtBuilder::default()
+        .min_creator_balance(INITIAL_BALANCE)
+        .build()
+        .execute_with(|| {
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+            // The deposit of the first kitty took ALICE below the threshold
+            assert_eq!(Balances::reserved_balance(ALICE), KITTY_DEPOSIT);
+            assert_noop!(
+                KittiesModule::create(Origin::signed(ALICE)),
+                Error::<Test>::InsufficientBalance
+            );
+            assert_noop!(
+                KittiesModule::create_batch(Origin::signed(ALICE), 2),
+                Error::<Test>::InsufficientBalance
+            );
+            assert_ok!(KittiesModule::create(Origin::signed(BOB)));
+        });
+}
+
+#[test]
+fn create_and_breed_wait_for_min_block_for_creation() {
+    ExtBuilder::default()
+        .min_block_for_creation(10)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            assert_noop!(
+                KittiesModule::create(Origin::signed(ALICE)),
+                Error::<Test>::RandomnessNotReady
+            );
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::RandomnessNotReady
+            );
+
+            run_to_block(10);
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        });
+}
+
+#[test]
+fn create_fails_when_owner_cap_is_reached() {
+    ExtBuilder::default()
+        .max_kitties_owned(2)
+        .build()
+        .execute_with(|| {
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+            assert_noop!(
+                KittiesModule::create(Origin::signed(ALICE)),
+                Error::<Test>::TooManyKitties
+            );
+        });
+}
+
+#[test]
+fn genesis_config_seeds_kitties() {
+    ExtBuilder::default()
+        .genesis_kitties(vec![(ALICE, [2u8; 16]), (BOB, [3u8; 16])])
+        .build()
+        .execute_with(|| {
+            assert_eq!(KittiesModule::kitties(0), Some(Kitty([2u8; 16])));
+            assert_eq!(KittiesModule::kitties(1), Some(Kitty([3u8; 16])));
+            assert_eq!(KittiesModule::owner_of(0), Some(ALICE));
+            assert_eq!(KittiesModule::owner_of(1), Some(BOB));
+            assert_eq!(KittiesModule::next_kitty_id(), 2);
+            assert_eq!(KittiesModule::kitty_count(), 2);
+            assert_eq!(KittiesModule::owned(BOB).to_vec(), vec![1]);
+            assert_eq!(KittiesModule::owner_index_of(&BOB, 1), Some(0));
+            assert_eq!(KittiesModule::history_of(1).to_vec(), vec![BOB]);
+
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_eq!(KittiesModule::owner_of(2), Some(ALICE));
+        });
+}
+
+#[test]
+#[should_panic(expected = "genesis kitties must respect MaxKittiesOwned")]
+fn genesis_config_rejects_owners_above_the_cap() {
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .genesis_kitties(vec![(ALICE, [2u8; 16]), (ALICE, [3u8; 16])])
+        .build();
+}
+
+#[test]
+fn gift_random_mints_for_recipient() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::gift_random(Origin::signed(CHARLIE), ALICE));
+
+        assert_eq!(KittiesModule::owner_of(0), Some(ALICE));
+        assert_eq!(KittiesModule::owned_count(CHARLIE), 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), KITTY_DEPOSIT);
+
+        let kitty = KittiesModule::kitties(0).unwrap();
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 2].event,
+            mock::Event::from(crate::Event::KittyCreated(
+                ALICE,
+                0,
+                kitty.clone(),
+                kitty.gender()
+            ))
+        );
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyGifted(CHARLIE, ALICE, 0))
+        );
+    });
+}
+
+#[test]
+fn gift_random_works_for_an_unfunded_recipient() {
+    new_test_ext().execute_with(|| {
+        let player = 42;
+        assert_eq!(Balances::free_balance(player), 0);
+
+        assert_ok!(KittiesModule::gift_random(Origin::signed(CHARLIE), player));
+        assert_eq!(KittiesModule::owner_of(0), Some(player));
+        assert_eq!(
+            KittyDeposits::<Test>::get(0),
+            Some((CHARLIE, KITTY_DEPOSIT))
+        );
+
+        // Burning hands the deposit back to the gifter
+        assert_ok!(KittiesModule::burn(Origin::signed(player), 0));
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn gift_random_requires_gift_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KittiesModule::gift_random(Origin::signed(ALICE), BOB),
+            sp_runtime::traits::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn gift_random_respects_recipient_cap() {
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .build()
+        .execute_with(|| {
+            assert_ok!(KittiesModule::gift_random(Origin::signed(CHARLIE), ALICE));
+            assert_noop!(
+                KittiesModule::gift_random(Origin::signed(CHARLIE), ALICE),
+                Error::<Test>::TooManyKitties
+            );
+        });
+}
+
+#[test]
+fn dna_helpers_match_previous_formulas() {
+    new_test_ext().execute_with(|| {
+        let seed = H256::repeat_byte(7);
+
+        set_random_seed(seed);
+        assert_eq!(
+            KittiesModule::generate_dna(&ALICE).ok(),
+            Some((seed, ALICE, None::<u32>, 1u64, 0u32).using_encoded(blake2_128))
+        );
+
+        set_random_seed(seed);
+        let payload = KittiesModule::random_payload(&ALICE).expect("seed is not zero");
+        assert_eq!(
+            KittiesModule::breeding_selector(&payload, 0),
+            (seed, ALICE, None::<u32>, 1u64, 0u32).using_encoded(blake2_128)
+        );
+        assert_eq!(
+            KittiesModule::breeding_selector(&payload, 2),
+            (seed, ALICE, None::<u32>, 1u64, 0u32, 2u32).using_encoded(blake2_128)
+        );
+    });
+}
+
+#[test]
+fn zero_random_seed_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+
+        set_random_seed(H256::zero());
+        assert_noop!(
+            KittiesModule::create(Origin::signed(ALICE)),
+            Error::<Test>::RandomnessNotAvailable
+        );
+
+        set_random_seed(H256::zero());
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::RandomnessNotAvailable
+        );
+
+        set_random_seed(H256::repeat_byte(1));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+    });
+}
+
+#[test]
+fn create_twice_in_one_block_gives_distinct_dna() {
+    new_test_ext().execute_with(|| {
+        let seed = H256::repeat_byte(7);
+
+        set_random_seed(seed);
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        set_random_seed(seed);
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_eq!(KittiesModule::account_nonce(ALICE), 2);
+        assert_ne!(KittiesModule::kitties(0), KittiesModule::kitties(1));
+    });
+}
+
+#[test]
+fn create_batch_mints_distinct_kitties() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create_batch(Origin::signed(ALICE), 3));
+
+        assert_eq!(KittiesModule::next_kitty_id(), 3);
+        assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![0, 1, 2]);
+        assert_eq!(Balances::reserved_balance(ALICE), 3 * KITTY_DEPOSIT);
+
+        let kitty0 = KittiesModule::kitties(0).unwrap();
+        let kitty1 = KittiesModule::kitties(1).unwrap();
+        let kitty2 = KittiesModule::kitties(2).unwrap();
+        assert_ne!(kitty0, kitty1);
+        assert_ne!(kitty1, kitty2);
+        assert_ne!(kitty0, kitty2);
+
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyCreated(
+                ALICE,
+                2,
+                kitty2.clone(),
+                kitty2.gender()
+            ))
+        );
+    });
+}
+
+#[test]
+fn create_batch_fails_above_max_batch_size() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KittiesModule::create_batch(Origin::signed(ALICE), MaxBatchSize::get() + 1),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn id_threshold_warning_is_emitted_once() {
+    ExtBuilder::default()
+        .id_warning_threshold(2)
+        .build()
+        .execute_with(|| {
+            let threshold_event = mock::Event::from(crate::Event::KittyIdThresholdReached(2));
+            let threshold_events = || {
+                System::events()
+                    .into_iter()
+                    .filter(|record| record.event == threshold_event)
+                    .count()
+            };
+
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_eq!(threshold_events(), 0);
+
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_eq!(threshold_events(), 1);
+            assert!(KittiesModule::id_threshold_reached());
+        });
+}
+
+fn breed_precheck_weight() -> Weight {
+    <() as WeightInfo>::breed_precheck()
+}
+
+#[test]
+fn breed_refunds_weight_when_pre_checks_fail() {
+    new_test_ext().execute_with(|| {
+        let kitty_id_1 = insert_kitty(ALICE, [0u8; 16]);
+        let kitty_id_2 = insert_kitty(ALICE, [2u8; 16]);
+
+        let err = KittiesModule::breed(Origin::signed(ALICE), kitty_id_1, kitty_id_2)
+            .expect_err("same gender parents can't breed");
+        assert_eq!(err.post_info.actual_weight, Some(breed_precheck_weight()));
+        assert_eq!(err.post_info.pays_fee, Pays::Yes);
+        assert!(breed_precheck_weight() < <() as WeightInfo>::breed());
+    });
+}
+
+#[test]
+fn mint_fails_once_max_supply_is_reached() {
+    ExtBuilder::default()
+        .max_supply(3)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0u8; 16]);
+            let female = insert_kitty(ALICE, [1u8; 16]);
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_eq!(KittiesModule::next_kitty_id(), 3);
+
+            assert_noop!(
+                KittiesModule::create(Origin::signed(ALICE)),
+                Error::<Test>::MaxSupplyReached
+            );
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::MaxSupplyReached
+            );
+        });
+}
+
+#[test]
+fn breed_batch_breeds_every_pair() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0u8; 16]);
+        let female = insert_kitty(ALICE, [1u8; 16]);
+        let other_male = insert_kitty(ALICE, [2u8; 16]);
+        let other_female = insert_kitty(ALICE, [3u8; 16]);
+
+        assert_ok!(KittiesModule::breed_batch(
+            Origin::signed(ALICE),
+            vec![(male, female), (other_male, other_female), (male, female)]
+        ));
+
+        assert_eq!(
+            KittiesModule::owned(ALICE).to_vec(),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(KittiesModule::parents_of(4), Some((male, female)));
+        assert_eq!(
+            KittiesModule::parents_of(5),
+            Some((other_male, other_female))
+        );
+        assert_eq!(KittiesModule::parents_of(6), Some((male, female)));
+        assert_ne!(KittiesModule::kitties(4), KittiesModule::kitties(6));
+        assert_eq!(KittiesModule::total_bred(), 3);
+
+        let child = KittiesModule::kitties(6).unwrap();
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyBred(
+                ALICE,
+                6,
+                child.clone(),
+                child.gender(),
+                1
+            ))
+        );
+    });
+}
+
+#[test]
+fn breed_batch_is_atomic() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0u8; 16]);
+        let female = insert_kitty(ALICE, [1u8; 16]);
+        let other_male = insert_kitty(ALICE, [2u8; 16]);
+
+        assert_noop!(
+            KittiesModule::breed_batch(
+                Origin::signed(ALICE),
+                vec![(male, female), (male, other_male)]
+            ),
+            Error::<Test>::SameGender
+        );
+        assert_noop!(
+            KittiesModule::breed_batch(
+                Origin::signed(ALICE),
+                vec![(male, female); MaxBatchSize::get() as usize + 1]
+            ),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn create_with_dna_sets_up_exact_parents() {
+    new_test_ext().execute_with(|| {
+        let male = KittiesModule::create_with_dna(&ALICE, [0x02; 16]).unwrap();
+        let female = KittiesModule::create_with_dna(&ALICE, [0x03; 16]).unwrap();
+        assert_eq!(KittiesModule::gender_of(male), Some(KittyGender::Male));
+        assert_eq!(KittiesModule::gender_of(female), Some(KittyGender::Female));
+        assert_eq!(Balances::reserved_balance(ALICE), 2 * KITTY_DEPOSIT);
+
+        assert_noop!(
+            KittiesModule::create_with_dna(&BOB, [0x02; 16]),
+            Error::<Test>::DuplicateDna
+        );
+
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        assert_eq!(KittiesModule::parents_of(2), Some((male, female)));
+    });
+}
+
+#[test]
+fn ensure_owns_all_stops_at_first_foreign_id() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0u8; 16]);
+        let female = insert_kitty(ALICE, [1u8; 16]);
+        let bobs_male = insert_kitty(BOB, [2u8; 16]);
+        let other_female = insert_kitty(ALICE, [3u8; 16]);
+
+        assert_ok!(KittiesModule::ensure_owns_all(
+            &ALICE,
+            &[male, female, other_female]
+        ));
+        assert_noop!(
+            KittiesModule::ensure_owns_all(&ALICE, &[male, bobs_male, 42]),
+            Error::<Test>::InvalidKittyId
+        );
+
+        // The foreign pair in the middle fails the batch before the first pair is bred
+        assert_noop!(
+            KittiesModule::breed_batch(
+                Origin::signed(ALICE),
+                vec![
+                    (male, female),
+                    (bobs_male, other_female),
+                    (male, other_female)
+                ]
+            ),
+            Error::<Test>::InvalidKittyId
+        );
+    });
+}
+
+#[test]
+fn fuse_burns_parents_for_a_rare_offspring() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0x01; 16]);
+        let other_male = insert_kitty(ALICE, [0x02; 16]);
+
+        assert_noop!(
+            KittiesModule::fuse(Origin::signed(ALICE), male, other_male),
+            Error::<Test>::SameGender
+        );
+        assert_noop!(
+            KittiesModule::fuse(Origin::signed(BOB), male, female),
+            Error::<Test>::InvalidKittyId
+        );
+
+        assert_ok!(KittiesModule::fuse(Origin::signed(ALICE), male, female));
+
+        assert!(!KittiesModule::kitty_exists(male));
+        assert!(!KittiesModule::kitty_exists(female));
+        assert!(KittiesModule::is_dead(male) && KittiesModule::is_dead(female));
+        assert_eq!(KittiesModule::owned_count(ALICE), 2);
+        assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![other_male, 3]);
+
+        let offspring = KittiesModule::kitties(3).expect("offspring exists");
+        assert!(offspring.rarity_score() >= 16);
+        assert_eq!(KittiesModule::parents_of(3), Some((male, female)));
+        assert_eq!(KittiesModule::generation_of(3), 1);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittiesFused(male, female, 3))
+        );
+    });
+}
+
+#[test]
+fn fuse_follows_the_breeding_rules() {
+    ExtBuilder::default()
+        .breed_cooldown_period(5)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0x01; 16]);
+
+            assert_ok!(KittiesModule::set_breeding_paused(Origin::root(), true));
+            assert_noop!(
+                KittiesModule::fuse(Origin::signed(ALICE), male, female),
+                Error::<Test>::BreedingPaused
+            );
+            assert_ok!(KittiesModule::set_breeding_paused(Origin::root(), false));
+
+            assert_ok!(KittiesModule::set_sterile(Origin::signed(ALICE), female));
+            assert_noop!(
+                KittiesModule::fuse(Origin::signed(ALICE), male, female),
+                Error::<Test>::KittySterile
+            );
+
+            // A kitty cooling down from a breeding can't be fused either
+            let other_female = insert_kitty(ALICE, [0x03; 16]);
+            assert_ok!(KittiesModule::breed(
+                Origin::signed(ALICE),
+                male,
+                other_female
+            ));
+            assert_noop!(
+                KittiesModule::fuse(Origin::signed(ALICE), male, other_female),
+                Error::<Test>::KittyOnCooldown
+            );
+        });
+}
+
+#[test]
+fn breed_fails_for_same_gender() {
+    new_test_ext().execute_with(|| {
+        let kitty_id_1 = insert_kitty(ALICE, [0u8; 16]);
+        let kitty_id_2 = insert_kitty(ALICE, [2u8; 16]);
+
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), kitty_id_1, kitty_id_2),
+            Error::<Test>::SameGender.with_weight(breed_precheck_weight())
+        );
+    });
+}
+
+#[test]
+fn breed_fails_for_unknown_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [0u8; 16]);
+
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), kitty_id, 42),
+            Error::<Test>::InvalidKittyId.with_weight(breed_precheck_weight())
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(BOB), kitty_id, 42),
+            Error::<Test>::InvalidKittyId.with_weight(breed_precheck_weight())
+        );
+    });
+}
+
+#[test]
+fn breed_with_self_fails() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), 5, 5),
+            Error::<Test>::BreedWithSelf.with_weight(breed_precheck_weight())
+        );
+    });
+}
+
+#[test]
+fn breed_owned_kitty_with_itself_fails_before_gender_check() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..4 {
+            insert_kitty(ALICE, [0x00; 16]);
+        }
+
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), 3, 3),
+            Error::<Test>::BreedWithSelf.with_weight(breed_precheck_weight())
+        );
+    });
+}
+
+#[test]
+fn breed_mixes_dna_with_selector() {
+    new_test_ext().execute_with(|| {
+        let seed = H256::repeat_byte(7);
+        set_random_seed(seed);
+
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+        // Selector bits pick the first parent, the remaining bits come from the second one.
+        // With an all-zero and an all-one parent the child DNA is the inverted selector.
+        let selector = (seed, ALICE, None::<u32>, 1u64, 0u32).using_encoded(blake2_128);
+        let mut expected = [0u8; 16];
+        for (byte, selector_byte) in expected.iter_mut().zip(selector.iter()) {
+            *byte = !selector_byte;
+        }
+
+        let child = KittiesModule::kitties(2).expect("child exists");
+        assert_eq!(child, Kitty(expected));
+        assert_eq!(KittiesModule::parents_of(2), Some((male, female)));
+        assert_eq!(KittiesModule::generation_of(2), 1);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyBred(
+                ALICE,
+                2,
+                Kitty(expected),
+                KittyGender::from_dna(&expected),
+                1
+            ))
+        );
+    });
+}
+
+#[test]
+fn breed_can_produce_a_litter() {
+    ExtBuilder::default()
+        .max_litter_size(3)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            // The default seed rolls a litter of three
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+            assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![0, 1, 2, 3, 4]);
+            assert_eq!(KittiesModule::total_bred(), 3);
+            for kitty_id in 2..5 {
+                assert_eq!(KittiesModule::parents_of(kitty_id), Some((male, female)));
+                assert_eq!(KittiesModule::generation_of(kitty_id), 1);
+            }
+            assert_ne!(KittiesModule::kitties(2), KittiesModule::kitties(3));
+            assert_ne!(KittiesModule::kitties(3), KittiesModule::kitties(4));
+            assert_ne!(KittiesModule::kitties(2), KittiesModule::kitties(4));
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::LitterBorn(male, female, 3, 3))
+            );
+        });
+}
+
+#[test]
+fn litter_is_truncated_at_ownership_cap() {
+    ExtBuilder::default()
+        .max_litter_size(3)
+        .max_kitties_owned(3)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+            assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![0, 1, 2]);
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::LitterBorn(male, female, 1, 3))
+            );
+        });
+}
+
+#[test]
+fn breed_with_requires_partner_approval() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(BOB, [0xff; 16]);
+
+        assert_noop!(
+            KittiesModule::breed_with(Origin::signed(ALICE), male, BOB, female),
+            Error::<Test>::BreedingNotApproved
+        );
+        assert_noop!(
+            KittiesModule::approve_breeding(Origin::signed(ALICE), female, ALICE),
+            Error::<Test>::InvalidKittyId
+        );
+
+        assert_ok!(KittiesModule::approve_breeding(
+            Origin::signed(BOB),
+            female,
+            ALICE
+        ));
+        assert_ok!(KittiesModule::breed_with(
+            Origin::signed(ALICE),
+            male,
+            BOB,
+            female
+        ));
+
+        assert_eq!(KittiesModule::owner_of(2), Some(ALICE));
+        assert_eq!(KittiesModule::parents_of(2), Some((male, female)));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::CoBred(ALICE, BOB, 2))
+        );
+
+        // The approval is used up by the first breeding
+        assert_eq!(KittiesModule::breeding_approvals(BOB, female), None);
+    });
+}
+
+#[test]
+fn breed_with_rejects_same_gender() {
+    new_test_ext().execute_with(|| {
+        let first = insert_kitty(ALICE, [0x00; 16]);
+        let second = insert_kitty(BOB, [0x00; 16]);
+        assert_ok!(KittiesModule::approve_breeding(
+            Origin::signed(BOB),
+            second,
+            ALICE
+        ));
+
+        assert_noop!(
+            KittiesModule::breed_with(Origin::signed(ALICE), first, BOB, second),
+            Error::<Test>::SameGender
+        );
+    });
+}
+
+#[test]
+fn breed_offspring_does_not_depend_on_parent_order() {
+    let offspring = |swap: bool| {
+        new_test_ext().execute_with(|| {
+            set_random_seed(H256::repeat_byte(7));
+            let male = insert_kitty(ALICE, [0x3c; 16]);
+            let female = insert_kitty(ALICE, [0xa5; 16]);
+
+            let (first, second) = if swap { (female, male) } else { (male, female) };
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), first, second));
+            KittiesModule::kitties(2).expect("child exists")
+        })
+    };
+
+    assert_eq!(offspring(false), offspring(true));
+}
+
+#[test]
+fn breed_mutates_offspring_bytes() {
+    ExtBuilder::default()
+        .mutation_rate(128)
+        .build()
+        .execute_with(|| {
+            let seed = H256::repeat_byte(7);
+            set_random_seed(seed);
+
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+            let selector = (seed, ALICE, None::<u32>, 1u64, 0u32).using_encoded(blake2_128);
+            let entropy = selector.using_encoded(sp_io::hashing::blake2_256);
+            let mut expected = [0u8; 16];
+            let mut mutated_bytes = 0;
+            for (i, byte) in expected.iter_mut().enumerate() {
+                *byte = if entropy[i] < 128 {
+                    mutated_bytes += 1;
+                    entropy[16 + i]
+                } else {
+                    !selector[i]
+                };
+            }
+            assert!(mutated_bytes > 0);
+
+            assert_eq!(KittiesModule::kitties(2), Some(Kitty(expected)));
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::KittyMutated(2, mutated_bytes))
+            );
+        });
+}
+
+#[test]
+fn paused_breeding_fails_until_resumed() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+
+        assert_noop!(
+            KittiesModule::set_breeding_paused(Origin::signed(ALICE), true),
+            sp_runtime::traits::BadOrigin
+        );
+        assert_ok!(KittiesModule::set_breeding_paused(Origin::root(), true));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::BreedingPauseToggled(true))
+        );
+
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::BreedingPaused
+        );
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_ok!(KittiesModule::set_breeding_paused(Origin::root(), false));
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+    });
+}
+
+#[test]
+fn paused_pallet_rejects_extrinsics_until_resumed() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        let bobs = insert_kitty(BOB, [0x02; 16]);
+        let listed = insert_kitty(BOB, [0x04; 16]);
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(BOB),
+            listed,
+            Some(10),
+            None
+        ));
+        let auctioned = insert_kitty(BOB, [0x06; 16]);
+        assert_ok!(KittiesModule::start_auction(
+            Origin::signed(BOB),
+            auctioned,
+            5,
+            10
+        ));
+        let abandoned = insert_kitty(BOB, [0x08; 16]);
+        assert_ok!(KittiesModule::abandon(Origin::signed(BOB), abandoned));
+        assert_ok!(KittiesModule::approve_swap(
+            Origin::signed(BOB),
+            bobs,
+            ALICE,
+            male
+        ));
+
+        assert_noop!(
+            KittiesModule::set_paused(Origin::signed(ALICE), true),
+            sp_runtime::traits::BadOrigin
+        );
+        assert_ok!(KittiesModule::set_paused(Origin::root(), true));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::PalletPauseToggled(true))
+        );
+
+        assert_noop!(
+            KittiesModule::create(Origin::signed(ALICE)),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), BOB, male),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(ALICE), male, Some(10), None),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::burn(Origin::signed(ALICE), male),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::buy(Origin::signed(ALICE), BOB, listed, 10),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::start_auction(Origin::signed(ALICE), male, 5, 10),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(ALICE), auctioned, 5),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::swap(Origin::signed(ALICE), male, BOB, bobs),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::abandon(Origin::signed(ALICE), male),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::claim(Origin::signed(ALICE), abandoned),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::set_name(Origin::signed(ALICE), male, b"Tom".to_vec()),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::transfer_many(Origin::signed(ALICE), BOB, vec![male]),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::gift_random(Origin::signed(CHARLIE), ALICE),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            KittiesModule::reserve_for_breeding(Origin::signed(ALICE), male, BOB, bobs, 10),
+            Error::<Test>::PalletPaused
+        );
+        assert_ok!(KittiesModule::force_transfer(
+            Origin::root(),
+            ALICE,
+            BOB,
+            female
+        ));
+
+        assert_ok!(KittiesModule::set_paused(Origin::root(), false));
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::buy(Origin::signed(ALICE), BOB, listed, 10));
+        assert_ok!(KittiesModule::claim(Origin::signed(ALICE), abandoned));
+        assert_ok!(KittiesModule::swap(Origin::signed(ALICE), male, BOB, bobs));
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, bobs));
+    });
+}
+
+#[test]
+fn same_color_breeding_depends_on_the_flag() {
+    let mut male_dna = [0u8; 16];
+    male_dna[1] = 3;
+    let mut female_dna = [1u8; 16];
+    female_dna[1] = 11;
+
+    ExtBuilder::default()
+        .forbid_same_color_breeding(true)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, male_dna);
+            let female = insert_kitty(ALICE, female_dna);
+
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::SameColor
+            );
+        });
+
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, male_dna);
+        let female = insert_kitty(ALICE, female_dna);
+
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+    });
+}
+
+#[test]
+fn breed_requires_min_generation() {
+    ExtBuilder::default()
+        .min_breeding_generation(1)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::GenerationTooLow
+            );
+
+            KittyGeneration::<Test>::insert(male, 1);
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::GenerationTooLow
+            );
+
+            KittyGeneration::<Test>::insert(female, 2);
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_eq!(KittiesModule::generation_of(2), 3);
+        });
+}
+
+#[test]
+fn simulate_breed_previews_the_real_offspring() {
+    ExtBuilder::default()
+        .mutation_rate(128)
+        .build()
+        .execute_with(|| {
+            let seed = H256::repeat_byte(7);
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+            let other_male = insert_kitty(ALICE, [0x02; 16]);
+
+            assert!(matches!(
+                KittiesModule::simulate_breed(&ALICE, male, other_male, [0u8; 16]),
+                Err(Error::<Test>::SameGender)
+            ));
+            assert!(matches!(
+                KittiesModule::simulate_breed(&BOB, male, female, [0u8; 16]),
+                Err(Error::<Test>::InvalidKittyId)
+            ));
+
+            let selector = (seed, ALICE, None::<u32>, 1u64, 0u32).using_encoded(blake2_128);
+            let preview = KittiesModule::simulate_breed(&ALICE, male, female, selector)
+                .expect("parents can breed");
+            assert_eq!(KittiesModule::next_kitty_id(), 3);
+
+            set_random_seed(seed);
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_eq!(KittiesModule::kitties(3), Some(preview));
+
+            BreedingPaused::<Test>::put(true);
+            assert!(matches!(
+                KittiesModule::simulate_breed(&ALICE, male, female, selector),
+                Err(Error::<Test>::BreedingPaused)
+            ));
+        });
+}
+
+#[test]
+fn breed_fee_is_split_between_burn_and_treasury() {
+    ExtBuilder::default()
+        .breed_fee(10)
+        .burn_ratio(Permill::from_percent(33))
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+            let issuance = Balances::total_issuance();
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+            // 33% of 10 rounds down to 3, the treasury gets the remaining 7
+            assert_eq!(
+                Balances::free_balance(ALICE),
+                INITIAL_BALANCE - 10 - KITTY_DEPOSIT
+            );
+            assert_eq!(Balances::free_balance(FEE_COLLECTOR), 7);
+            assert_eq!(Balances::total_issuance(), issuance - 3);
+            assert!(System::events()
+                .iter()
+                .any(|record| record.event
+                    == mock::Event::from(crate::Event::BreedFeeCollected(3, 7))));
+
+            let _ = Balances::make_free_balance_be(&ALICE, 5);
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::InsufficientBalance
+            );
+        });
+}
+
+#[test]
+fn breed_stops_once_fertility_is_exhausted() {
+    ExtBuilder::default()
+        .max_breedings(2)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+            let other_female = insert_kitty(ALICE, [0x01; 16]);
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_eq!(KittiesModule::breed_count(male), 2);
+            assert_eq!(KittiesModule::breed_count(female), 2);
+
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::FertilityExhausted
+            );
+            // One exhausted parent is enough to refuse the pair
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, other_female),
+                Error::<Test>::FertilityExhausted
+            );
+            assert_eq!(KittiesModule::breed_count(other_female), 0);
+        });
+}
+
+#[test]
+fn breed_waits_for_maturity() {
+    ExtBuilder::default()
+        .maturity_period(5)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0x00; 16]);
+            let female = insert_kitty(ALICE, [0xff; 16]);
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            let child = 2;
+            assert_eq!(KittiesModule::birth_block(child), Some(1));
+            assert!(!KittiesModule::is_mature(child));
+
+            let partner = match KittiesModule::gender_of(child) {
+                Some(KittyGender::Male) => female,
+                _ => male,
+            };
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), child, partner),
+                Error::<Test>::KittyTooYoung
+            );
+
+            run_to_block(5);
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), child, partner),
+                Error::<Test>::KittyTooYoung
+            );
+
+            run_to_block(6);
+            assert!(KittiesModule::is_mature(child));
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), child, partner));
+        });
+}
+
+#[test]
+fn breed_cooldown_expires() {
+    ExtBuilder::default()
+        .breed_cooldown_period(5)
+        .build()
+        .execute_with(|| {
+            let male = insert_kitty(ALICE, [0u8; 16]);
+            let female = insert_kitty(ALICE, [1u8; 16]);
+
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+            assert_eq!(KittiesModule::cooldown_of(male), Some(6));
+            assert_eq!(KittiesModule::cooldown_of(female), Some(6));
+
+            run_to_block(5);
+            assert_noop!(
+                KittiesModule::breed(Origin::signed(ALICE), male, female),
+                Error::<Test>::KittyOnCooldown
+            );
+
+            run_to_block(6);
+            assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        });
+}
+
+#[test]
+fn transfer_moves_kitty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 0));
+
+        assert_eq!(KittiesModule::owner_of(0), Some(BOB));
+        assert_eq!(KittiesModule::owned_count(ALICE), 0);
+        assert_eq!(KittiesModule::owned_count(BOB), 1);
+        assert!(KittiesModule::owned(ALICE).is_empty());
+        assert_eq!(KittiesModule::owned(BOB).to_vec(), vec![0]);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyTransferred(ALICE, BOB, 0))
+        );
+    });
+}
+
+#[test]
+fn transfer_decrements_owned_count_once() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(KittiesModule::owned_count(ALICE), 1);
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 0));
+        assert_eq!(KittiesModule::owned_count(ALICE), 0);
+
+        // A second transfer of the same kitty must not decrement the count again
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), BOB, 0),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), CHARLIE, 0),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_eq!(KittiesModule::owned_count(ALICE), 0);
+        assert_eq!(KittiesModule::owned_count(BOB), 1);
+    });
+}
+
+#[test]
+fn swap_requires_counterparty_approval() {
+    new_test_ext().execute_with(|| {
+        let alices = insert_kitty(ALICE, [0x00; 16]);
+        let bobs = insert_kitty(BOB, [0x01; 16]);
+        let bobs_other = insert_kitty(BOB, [0x02; 16]);
+
+        assert_noop!(
+            KittiesModule::swap(Origin::signed(ALICE), alices, BOB, bobs),
+            Error::<Test>::SwapNotApproved
+        );
+
+        // An approval for another kitty of ALICE doesn't count
+        assert_ok!(KittiesModule::approve_swap(
+            Origin::signed(BOB),
+            bobs,
+            ALICE,
+            alices + 10
+        ));
+        assert_noop!(
+            KittiesModule::swap(Origin::signed(ALICE), alices, BOB, bobs),
+            Error::<Test>::SwapNotApproved
+        );
+        assert_noop!(
+            KittiesModule::approve_swap(Origin::signed(BOB), alices, ALICE, bobs),
+            Error::<Test>::InvalidKittyId
+        );
+
+        assert_ok!(KittiesModule::approve_swap(
+            Origin::signed(BOB),
+            bobs,
+            ALICE,
+            alices
+        ));
+        assert_noop!(
+            KittiesModule::swap(Origin::signed(ALICE), alices, BOB, bobs_other),
+            Error::<Test>::SwapNotApproved
+        );
+        assert_ok!(KittiesModule::swap(
+            Origin::signed(ALICE),
+            alices,
+            BOB,
+            bobs
+        ));
+
+        assert_eq!(KittiesModule::owner_of(alices), Some(BOB));
+        assert_eq!(KittiesModule::owner_of(bobs), Some(ALICE));
+        assert_eq!(KittiesModule::owned_count(ALICE), 1);
+        assert_eq!(KittiesModule::owned_count(BOB), 2);
+        assert_eq!(KittiesModule::swap_approvals(BOB, bobs), None);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittiesSwapped(ALICE, BOB, alices, bobs))
+        );
+    });
+}
+
+#[test]
+fn swap_works_for_owners_at_their_cap() {
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .build()
+        .execute_with(|| {
+            let alices = insert_kitty(ALICE, [0x00; 16]);
+            let bobs = insert_kitty(BOB, [0x01; 16]);
+
+            assert_ok!(KittiesModule::approve_swap(
+                Origin::signed(BOB),
+                bobs,
+                ALICE,
+                alices
+            ));
+            assert_ok!(KittiesModule::swap(
+                Origin::signed(ALICE),
+                alices,
+                BOB,
+                bobs
+            ));
+
+            assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![bobs]);
+            assert_eq!(KittiesModule::owned(BOB).to_vec(), vec![alices]);
+        });
+}
+
+#[test]
+fn kitty_events_are_indexed_by_kitty_and_owners() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        let kitty = KittiesModule::kitties(0).unwrap();
+        assert_eq!(
+            topics_of(crate::Event::KittyCreated(
+                ALICE,
+                0,
+                kitty.clone(),
+                kitty.gender()
+            )),
+            vec![BlakeTwo256::hash_of(&0u32), BlakeTwo256::hash_of(&ALICE)]
+        );
+
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        let child = KittiesModule::kitties(3).unwrap();
+        assert_eq!(
+            topics_of(crate::Event::KittyBred(
+                ALICE,
+                3,
+                child.clone(),
+                child.gender(),
+                1
+            )),
+            vec![BlakeTwo256::hash_of(&3u32), BlakeTwo256::hash_of(&ALICE)]
+        );
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 0));
+        assert_eq!(
+            topics_of(crate::Event::KittyTransferred(ALICE, BOB, 0)),
+            vec![
+                BlakeTwo256::hash_of(&0u32),
+                BlakeTwo256::hash_of(&ALICE),
+                BlakeTwo256::hash_of(&BOB)
+            ]
+        );
+        assert_eq!(System::event_topics(BlakeTwo256::hash_of(&BOB)).len(), 1);
+    });
+}
+
+#[test]
+fn transfer_to_self_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), ALICE, 0),
+            Error::<Test>::TransferToSelf
+        );
+    });
+}
+
+#[test]
+fn locked_kitty_cannot_be_transferred_burned_or_bred() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        KittiesModule::lock_kitty(male);
+        assert!(KittiesModule::is_locked(male));
+
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), BOB, male),
+            Error::<Test>::KittyLocked
+        );
+        assert_noop!(
+            KittiesModule::burn(Origin::signed(ALICE), male),
+            Error::<Test>::KittyLocked
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::KittyLocked
+        );
+
+        KittiesModule::unlock_kitty(male);
+        assert!(!KittiesModule::is_locked(male));
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, male));
+    });
+}
+
+#[test]
+fn force_transfer_requires_force_origin() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+
+        assert_noop!(
+            KittiesModule::force_transfer(Origin::signed(ALICE), ALICE, BOB, kitty_id),
+            sp_runtime::traits::BadOrigin
+        );
+        assert_noop!(
+            KittiesModule::force_transfer(Origin::root(), BOB, ALICE, kitty_id),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_eq!(KittiesModule::owner_of(kitty_id), Some(ALICE));
+    });
+}
+
+#[test]
+fn force_transfer_ignores_locks_but_not_the_cap() {
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .build()
+        .execute_with(|| {
+            let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+            let bobs = insert_kitty(BOB, [0x01; 16]);
+
+            assert_ok!(KittiesModule::start_auction(
+                Origin::signed(ALICE),
+                kitty_id,
+                100,
+                10
+            ));
+            assert_ok!(KittiesModule::bid(Origin::signed(CHARLIE), kitty_id, 100));
+
+            assert_noop!(
+                KittiesModule::force_transfer(Origin::root(), ALICE, BOB, kitty_id),
+                Error::<Test>::TooManyKitties
+            );
+            assert_ok!(KittiesModule::burn(Origin::signed(BOB), bobs));
+
+            assert_ok!(KittiesModule::force_transfer(
+                Origin::root(),
+                ALICE,
+                BOB,
+                kitty_id
+            ));
+            assert_eq!(KittiesModule::owner_of(kitty_id), Some(BOB));
+            assert!(!KittiesModule::is_locked(kitty_id));
+            assert_eq!(KittiesModule::auctions(kitty_id), None);
+            assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+            let events = System::events();
+            assert_eq!(
+                events[events.len() - 2].event,
+                mock::Event::from(crate::Event::AuctionCancelled(kitty_id, Some(CHARLIE), 100))
+            );
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::KittyForceTransferred(ALICE, BOB, kitty_id))
+            );
+        });
+}
+
+#[test]
+fn kitty_under_auction_cannot_be_reserved_for_breeding() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let partner = insert_kitty(BOB, [0xff; 16]);
+
+        assert_ok!(KittiesModule::start_auction(
+            Origin::signed(ALICE),
+            male,
+            10,
+            5
+        ));
+        assert_noop!(
+            KittiesModule::reserve_for_breeding(Origin::signed(ALICE), male, BOB, partner, 10),
+            Error::<Test>::KittyLocked
+        );
+    });
+}
+
+#[test]
+fn reserved_kitty_only_breeds_with_its_partner() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let other_female = insert_kitty(ALICE, [0x01; 16]);
+        let partner = insert_kitty(BOB, [0xff; 16]);
+
+        assert_ok!(KittiesModule::reserve_for_breeding(
+            Origin::signed(ALICE),
+            male,
+            BOB,
+            partner,
+            10
+        ));
+        assert_noop!(
+            KittiesModule::reserve_for_breeding(Origin::signed(ALICE), male, BOB, partner, 20),
+            Error::<Test>::KittyReservedForBreeding
+        );
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), CHARLIE, male),
+            Error::<Test>::KittyReservedForBreeding
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, other_female),
+            Error::<Test>::KittyReservedForBreeding
+        );
+
+        assert_eq!(Balances::reserved_balance(ALICE), BREEDING_DEPOSIT);
+        assert_ok!(KittiesModule::breed_with(
+            Origin::signed(BOB),
+            partner,
+            ALICE,
+            male
+        ));
+        assert_eq!(KittiesModule::breeding_locks(male), None);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_ok!(KittiesModule::transfer(
+            Origin::signed(ALICE),
+            CHARLIE,
+            male
+        ));
+    });
+}
+
+#[test]
+fn force_transfer_slashes_breeding_deposit() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let partner = insert_kitty(BOB, [0xff; 16]);
+
+        assert_ok!(KittiesModule::reserve_for_breeding(
+            Origin::signed(ALICE),
+            male,
+            BOB,
+            partner,
+            10
+        ));
+        assert_ok!(KittiesModule::force_transfer(
+            Origin::root(),
+            ALICE,
+            CHARLIE,
+            male
+        ));
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - BREEDING_DEPOSIT
+        );
+        assert_eq!(KittiesModule::breeding_deposits(male), None);
+        assert_eq!(KittiesModule::breeding_locks(male), None);
+        assert!(System::events().iter().any(|record| record.event
+            == mock::Event::from(crate::Event::BreedingDepositSlashed(
+                ALICE,
+                BREEDING_DEPOSIT
+            ))));
+    });
+}
+
+#[test]
+fn breeding_reservation_runs_out_after_until_block() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let partner = insert_kitty(BOB, [0xff; 16]);
+
+        assert_ok!(KittiesModule::reserve_for_breeding(
+            Origin::signed(ALICE),
+            male,
+            BOB,
+            partner,
+            5
+        ));
+
+        run_to_block(5);
+        assert_eq!(KittiesModule::breeding_partner(male), Some(partner));
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), CHARLIE, male),
+            Error::<Test>::KittyReservedForBreeding
+        );
+
+        run_to_block(6);
+        assert_eq!(KittiesModule::breeding_partner(male), None);
+        assert_ok!(KittiesModule::transfer(
+            Origin::signed(ALICE),
+            CHARLIE,
+            male
+        ));
+        // The deposit comes back once the kitty moves on
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+    });
+}
+
+#[test]
+fn abandoned_kitty_can_be_claimed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        let kitty = KittiesModule::kitties(0).unwrap();
+
+        assert_ok!(KittiesModule::abandon(Origin::signed(ALICE), 0));
+        assert_eq!(KittiesModule::abandoned(0), Some(kitty.clone()));
+        assert_eq!(KittiesModule::owner_of(0), None);
+        assert!(KittiesModule::owned(ALICE).is_empty());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyAbandoned(ALICE, 0))
+        );
+
+        assert_ok!(KittiesModule::claim(Origin::signed(BOB), 0));
+        assert_eq!(KittiesModule::abandoned(0), None);
+        assert_eq!(KittiesModule::kitties(0), Some(kitty));
+        assert_eq!(KittiesModule::owner_of(0), Some(BOB));
+        assert_eq!(KittiesModule::owned(BOB).to_vec(), vec![0]);
+        assert_eq!(Balances::reserved_balance(BOB), KITTY_DEPOSIT);
+        assert_eq!(KittiesModule::history_of(0).to_vec(), vec![ALICE, BOB]);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyClaimed(BOB, 0))
+        );
+
+        assert_noop!(
+            KittiesModule::claim(Origin::signed(ALICE), 0),
+            Error::<Test>::KittyNotAbandoned
+        );
+    });
+}
+
+#[test]
+fn abandoned_kitties_leave_the_counts_until_claimed() {
+    new_test_ext().execute_with(|| {
+        insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        let counts = || {
+            (
+                KittiesModule::kitty_count(),
+                KittiesModule::male_count(),
+                KittiesModule::female_count(),
+            )
+        };
+        let stats = || {
+            let stats = KittiesModule::rarity_stats();
+            (stats.count, stats.males, stats.females)
+        };
+
+        assert_ok!(KittiesModule::abandon(Origin::signed(ALICE), female));
+        assert_eq!(counts(), (1, 1, 0));
+        assert_eq!(counts(), stats());
+        assert_eq!(KittiesModule::owned_count(ALICE) as u64, counts().0);
+
+        assert_ok!(KittiesModule::claim(Origin::signed(BOB), female));
+        assert_eq!(counts(), (2, 1, 1));
+        assert_eq!(counts(), stats());
+    });
+}
+
+#[test]
+fn abandon_drops_swap_approval() {
+    new_test_ext().execute_with(|| {
+        let alices = insert_kitty(ALICE, [0x00; 16]);
+        let bobs = insert_kitty(BOB, [0x01; 16]);
+        assert_ok!(KittiesModule::approve_swap(
+            Origin::signed(ALICE),
+            alices,
+            BOB,
+            bobs
+        ));
+
+        assert_ok!(KittiesModule::abandon(Origin::signed(ALICE), alices));
+        assert_eq!(KittiesModule::swap_approvals(ALICE, alices), None);
+
+        // Reclaiming the kitty does not bring the old offer back
+        assert_ok!(KittiesModule::claim(Origin::signed(ALICE), alices));
+        assert_noop!(
+            KittiesModule::swap(Origin::signed(BOB), bobs, ALICE, alices),
+            Error::<Test>::SwapNotApproved
+        );
+    });
+}
+
+#[test]
+fn claim_respects_ownership_cap() {
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .build()
+        .execute_with(|| {
+            assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+            assert_ok!(KittiesModule::create(Origin::signed(BOB)));
+            assert_ok!(KittiesModule::abandon(Origin::signed(ALICE), 0));
+
+            assert_noop!(
+                KittiesModule::claim(Origin::signed(BOB), 0),
+                Error::<Test>::TooManyKitties
+            );
+        });
+}
+
+#[test]
+fn sterile_kitty_cannot_breed_even_after_transfer() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+
+        assert_noop!(
+            KittiesModule::set_sterile(Origin::signed(BOB), male),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_ok!(KittiesModule::set_sterile(Origin::signed(ALICE), male));
+        assert!(KittiesModule::is_sterile(male));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittySterilized(male))
+        );
+
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::KittySterile
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), female, male),
+            Error::<Test>::KittySterile
+        );
+
+        let other_female = insert_kitty(BOB, [0xff; 16]);
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, male));
+        assert!(KittiesModule::is_sterile(male));
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(BOB), male, other_female),
+            Error::<Test>::KittySterile
+        );
+    });
+}
+
+#[test]
+fn admin_set_gender_overrides_dna() {
+    new_test_ext().execute_with(|| {
+        let first = insert_kitty(ALICE, [0x00; 16]);
+        let second = insert_kitty(ALICE, [0x00; 16]);
+
+        assert_noop!(
+            KittiesModule::admin_set_gender(Origin::signed(ALICE), first, KittyGender::Female),
+            sp_runtime::traits::BadOrigin
+        );
+        assert_noop!(
+            KittiesModule::admin_set_gender(Origin::root(), 42, KittyGender::Female),
+            Error::<Test>::InvalidKittyId
+        );
+
+        assert_ok!(KittiesModule::admin_set_gender(
+            Origin::root(),
+            first,
+            KittyGender::Female
+        ));
+        assert_eq!(KittiesModule::gender_of(first), Some(KittyGender::Female));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::GenderOverridden(first, KittyGender::Female))
+        );
+
+        // Both DNAs are male, but the override makes them a valid pair
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), first, second));
+    });
+}
+
+#[test]
+fn history_tracks_owners_and_drops_the_oldest() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_eq!(KittiesModule::history_of(0).to_vec(), vec![ALICE]);
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 0));
+        assert_ok!(KittiesModule::transfer(Origin::signed(BOB), CHARLIE, 0));
+        assert_eq!(
+            KittiesModule::history_of(0).to_vec(),
+            vec![ALICE, BOB, CHARLIE]
+        );
+
+        // `MaxHistory` is three in the mock
+        assert_ok!(KittiesModule::transfer(Origin::signed(CHARLIE), ALICE, 0));
+        assert_eq!(
+            KittiesModule::history_of(0).to_vec(),
+            vec![BOB, CHARLIE, ALICE]
+        );
+    });
+}
+
+#[test]
+fn transfer_all_moves_every_kitty() {
+    new_test_ext().execute_with(|| {
+        let first = insert_kitty(ALICE, [1u8; 16]);
+        let second = insert_kitty(ALICE, [2u8; 16]);
+        let third = insert_kitty(ALICE, [3u8; 16]);
+        insert_kitty(BOB, [4u8; 16]);
+
+        assert_ok!(KittiesModule::transfer_all(Origin::signed(ALICE), BOB));
+
+        for kitty_id in [first, second, third].iter() {
+            assert_eq!(KittiesModule::owner_of(kitty_id), Some(BOB));
+        }
+        assert_eq!(KittiesModule::owned_count(ALICE), 0);
+        assert_eq!(KittiesModule::owned_count(BOB), 4);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::AllKittiesTransferred(ALICE, BOB, 3))
+        );
+    });
+}
+
+#[test]
+fn transfer_many_moves_the_given_kitties() {
+    new_test_ext().execute_with(|| {
+        let first = insert_kitty(ALICE, [1u8; 16]);
+        let kept = insert_kitty(ALICE, [2u8; 16]);
+        let third = insert_kitty(ALICE, [3u8; 16]);
+
+        assert_ok!(KittiesModule::transfer_many(
+            Origin::signed(ALICE),
+            BOB,
+            vec![first, third]
+        ));
+
+        assert_eq!(KittiesModule::owner_of(first), Some(BOB));
+        assert_eq!(KittiesModule::owner_of(kept), Some(ALICE));
+        assert_eq!(KittiesModule::owner_of(third), Some(BOB));
+        for kitty_id in [first, third].iter() {
+            assert!(System::events().iter().any(|record| record.event
+                == mock::Event::from(crate::Event::KittyTransferred(ALICE, BOB, *kitty_id))));
+        }
+    });
+}
+
+#[test]
+fn transfer_many_aborts_on_any_unowned_kitty() {
+    ExtBuilder::default()
+        .max_kitties_owned(3)
+        .build()
+        .execute_with(|| {
+            let first = insert_kitty(ALICE, [1u8; 16]);
+            let second = insert_kitty(ALICE, [2u8; 16]);
+            let not_mine = insert_kitty(CHARLIE, [3u8; 16]);
+            insert_kitty(BOB, [4u8; 16]);
+
+            assert_noop!(
+                KittiesModule::transfer_many(
+                    Origin::signed(ALICE),
+                    BOB,
+                    vec![first, not_mine, second]
+                ),
+                Error::<Test>::InvalidKittyId
+            );
+            assert_noop!(
+                KittiesModule::transfer_many(Origin::signed(ALICE), BOB, vec![first, first]),
+                Error::<Test>::InvalidKittyId
+            );
+            insert_kitty(BOB, [5u8; 16]);
+            assert_noop!(
+                KittiesModule::transfer_many(Origin::signed(ALICE), BOB, vec![first, second]),
+                Error::<Test>::TooManyKitties
+            );
+        });
+}
+
+#[test]
+fn transfer_all_fails_early_when_recipient_is_full() {
+    ExtBuilder::default()
+        .max_kitties_owned(3)
+        .build()
+        .execute_with(|| {
+            insert_kitty(ALICE, [1u8; 16]);
+            insert_kitty(ALICE, [2u8; 16]);
+            insert_kitty(BOB, [3u8; 16]);
+            insert_kitty(BOB, [4u8; 16]);
+
+            assert_noop!(
+                KittiesModule::transfer_all(Origin::signed(ALICE), BOB),
+                Error::<Test>::TooManyKitties
+            );
+        });
+}
+
+#[test]
+fn transfer_from_requires_owner_or_approval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_noop!(
+            KittiesModule::approve(Origin::signed(BOB), BOB, 0),
+            Error::<Test>::NotOwner
+        );
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(BOB), ALICE, BOB, 0),
+            Error::<Test>::NotApproved
+        );
+        assert_noop!(
+            KittiesModule::transfer_from(Origin::signed(BOB), CHARLIE, BOB, 0),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(KittiesModule::approve(Origin::signed(ALICE), BOB, 0));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::Approval(ALICE, BOB, 0))
+        );
+
+        assert_ok!(KittiesModule::transfer_from(
+            Origin::signed(BOB),
+            ALICE,
+            CHARLIE,
+            0
+        ));
+        assert_eq!(KittiesModule::owner_of(0), Some(CHARLIE));
+        // The approval is cleared by the transfer.
+        assert_eq!(KittiesModule::approvals(0), None);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyTransferred(ALICE, CHARLIE, 0))
+        );
+    });
+}
+
+#[test]
+fn burn_removes_kitty_and_releases_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_noop!(
+            KittiesModule::burn(Origin::signed(BOB), 0),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 0));
+
+        assert!(KittiesModule::kitties(0).is_none());
+        assert_eq!(KittiesModule::owner_of(0), None);
+        assert!(KittiesModule::owned(ALICE).is_empty());
+        assert_eq!(KittiesModule::kitty_count(), 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+    });
+}
+
+#[test]
+fn burned_kitty_is_buried_in_graveyard() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        run_to_block(4);
+
+        assert!(!KittiesModule::is_dead(0));
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 0));
+
+        assert!(KittiesModule::is_dead(0));
+        assert_eq!(DeadKitties::<Test>::get(0), Some((ALICE, 4)));
+        assert!(!KittiesModule::kitty_exists(0));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyBuried(0, ALICE, 4))
+        );
+    });
+}
+
+#[test]
+fn cleanup_removes_only_expired_graveyard_entries() {
+    new_test_ext().execute_with(|| {
+        KittiesModule::bury(&ALICE, 0, 1);
+        KittiesModule::bury(&ALICE, 1, 2);
+        KittiesModule::bury(&BOB, 2, 3);
+        KittiesModule::bury(&BOB, 3, 8);
+        run_to_block(13);
+
+        assert_ok!(KittiesModule::cleanup_dead_kitties(
+            Origin::signed(CHARLIE),
+            2
+        ));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::GraveyardCleaned(2))
+        );
+        assert_eq!(DeadKitties::<Test>::iter().count(), 2);
+        assert!(KittiesModule::is_dead(3));
+
+        // Only one of the remaining entries is old enough
+        assert_ok!(KittiesModule::cleanup_dead_kitties(
+            Origin::signed(CHARLIE),
+            100
+        ));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::GraveyardCleaned(1))
+        );
+        assert!(!KittiesModule::is_dead(0));
+        assert!(!KittiesModule::is_dead(1));
+        assert!(!KittiesModule::is_dead(2));
+        assert!(KittiesModule::is_dead(3));
+        assert_eq!(KittiesModule::graveyard_range(), (3, 4));
+    });
+}
+
+#[test]
+fn cleaned_up_id_is_minted_without_the_dead_kittys_state() {
+    use frame_support::traits::tokens::nonfungibles::Mutate;
+
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+        let child = 2;
+        assert_eq!(KittiesModule::generation_of(child), 1);
+        assert_eq!(KittiesModule::breed_count(male), 1);
+
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), male));
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), child));
+        assert_eq!(KittiesModule::breed_count(male), 0);
+        assert_eq!(KittiesModule::cooldown_of(male), None);
+        assert_eq!(KittiesModule::parents_of(child), None);
+        assert_eq!(KittiesModule::birth_block(child), None);
+
+        // Still in the graveyard, the ids can't be minted again
+        assert_noop!(
+            <KittiesModule as Mutate<u64>>::mint_into(&(), &male, &BOB),
+            Error::<Test>::KittyAlreadyExists
+        );
+
+        run_to_block(11);
+        assert_ok!(KittiesModule::cleanup_dead_kitties(
+            Origin::signed(CHARLIE),
+            5
+        ));
+        assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&(), &male, &BOB));
+        assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&(), &child, &BOB));
+
+        assert_eq!(KittiesModule::owner_of(male), Some(BOB));
+        assert_eq!(KittiesModule::breed_count(male), 0);
+        assert_eq!(KittiesModule::cooldown_of(male), None);
+        assert_eq!(KittiesModule::generation_of(child), 0);
+        assert_eq!(KittiesModule::parents_of(child), None);
+        assert_eq!(KittiesModule::birth_block(child), Some(11));
+        assert_eq!(KittiesModule::history_of(child).to_vec(), vec![BOB]);
+    });
+}
+
+#[test]
+fn cleanup_stops_at_the_first_entry_within_retention() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 0));
+        run_to_block(5);
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 1));
+        assert_eq!(KittiesModule::graveyard_range(), (0, 2));
+
+        run_to_block(11);
+        assert_ok!(KittiesModule::cleanup_dead_kitties(
+            Origin::signed(CHARLIE),
+            5
+        ));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::GraveyardCleaned(1))
+        );
+        assert!(!KittiesModule::is_dead(0));
+        assert!(KittiesModule::is_dead(1));
+        assert_eq!(KittiesModule::graveyard_range(), (1, 2));
+        assert!(GraveyardQueue::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn dead_kitty_cannot_be_transferred_or_bred() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        // A graveyard entry lingering next to a live kitty still blocks it
+        DeadKitties::<Test>::insert(male, (ALICE, 1));
+
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), BOB, male),
+            Error::<Test>::KittyDead
+        );
+        assert_noop!(
+            KittiesModule::breed(Origin::signed(ALICE), male, female),
+            Error::<Test>::KittyDead
+        );
+    });
+}
+
+#[test]
+fn set_name_rejects_long_names() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_noop!(
+            KittiesModule::set_name(Origin::signed(ALICE), 0, b"much too long".to_vec()),
+            Error::<Test>::NameTooLong
+        );
+        assert_ok!(KittiesModule::set_name(
+            Origin::signed(ALICE),
+            0,
+            b"Tom".to_vec()
+        ));
+        assert_eq!(
+            KittiesModule::kitty_names(ALICE, 0).map(|name| name.to_vec()),
+            Some(b"Tom".to_vec())
+        );
+    });
+}
+
+#[test]
+fn attributes_can_be_set_overwritten_and_cleared() {
+    use frame_support::traits::tokens::nonfungibles::Inspect;
+
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+        let attribute = |key: &[u8]| {
+            let key: BoundedVec<u8, MaxKeyLen> = key.to_vec().try_into().unwrap();
+            KittiesModule::kitty_attributes(kitty_id, key).map(|value| value.to_vec())
+        };
+
+        assert_noop!(
+            KittiesModule::set_attribute(
+                Origin::signed(BOB),
+                kitty_id,
+                b"background".to_vec(),
+                b"forest".to_vec()
+            ),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_noop!(
+            KittiesModule::set_attribute(
+                Origin::signed(ALICE),
+                kitty_id,
+                b"background".to_vec(),
+                b"rainforest".to_vec()
+            ),
+            Error::<Test>::ValueTooLong
+        );
+
+        assert_ok!(KittiesModule::set_attribute(
+            Origin::signed(ALICE),
+            kitty_id,
+            b"background".to_vec(),
+            b"forest".to_vec()
+        ));
+        assert_ok!(KittiesModule::set_attribute(
+            Origin::signed(ALICE),
+            kitty_id,
+            b"background".to_vec(),
+            b"beach".to_vec()
+        ));
+        assert_eq!(attribute(b"background"), Some(b"beach".to_vec()));
+        assert_eq!(KittiesModule::attribute_count(kitty_id), 1);
+        assert_eq!(
+            <KittiesModule as Inspect<u64>>::attribute(&(), &kitty_id, b"background"),
+            Some(b"beach".to_vec())
+        );
+
+        assert_ok!(KittiesModule::set_attribute(
+            Origin::signed(ALICE),
+            kitty_id,
+            b"hat".to_vec(),
+            b"top".to_vec()
+        ));
+        assert_noop!(
+            KittiesModule::set_attribute(
+                Origin::signed(ALICE),
+                kitty_id,
+                b"collar".to_vec(),
+                b"red".to_vec()
+            ),
+            Error::<Test>::TooManyAttributes
+        );
+
+        assert_ok!(KittiesModule::clear_attribute(
+            Origin::signed(ALICE),
+            kitty_id,
+            b"background".to_vec()
+        ));
+        assert_eq!(attribute(b"background"), None);
+        assert_eq!(KittiesModule::attribute_count(kitty_id), 1);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::AttributeCleared(
+                kitty_id,
+                b"background".to_vec()
+            ))
+        );
+        assert_noop!(
+            KittiesModule::clear_attribute(Origin::signed(ALICE), kitty_id, b"background".to_vec()),
+            Error::<Test>::AttributeNotFound
+        );
+    });
+}
+
+#[test]
+fn unique_names_cannot_be_shared() {
+    ExtBuilder::default()
+        .unique_names(true)
+        .build()
+        .execute_with(|| {
+            let tom = insert_kitty(ALICE, [0x00; 16]);
+            let other = insert_kitty(BOB, [0x01; 16]);
+
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(ALICE),
+                tom,
+                b"Tom".to_vec()
+            ));
+            assert_eq!(KittiesModule::name_owner(bounded_name(b"Tom")), Some(tom));
+            assert_noop!(
+                KittiesModule::set_name(Origin::signed(BOB), other, b"Tom".to_vec()),
+                Error::<Test>::NameTaken
+            );
+
+            // Keeping the current name is not a conflict
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(ALICE),
+                tom,
+                b"Tom".to_vec()
+            ));
+
+            // Renaming frees the old name
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(ALICE),
+                tom,
+                b"Tim".to_vec()
+            ));
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(BOB),
+                other,
+                b"Tom".to_vec()
+            ));
+
+            // So does burning
+            assert_ok!(KittiesModule::burn(Origin::signed(ALICE), tom));
+            assert_eq!(KittiesModule::name_owner(bounded_name(b"Tim")), None);
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(BOB),
+                other,
+                b"Tim".to_vec()
+            ));
+            assert_eq!(KittiesModule::name_owner(bounded_name(b"Tom")), None);
+        });
+}
+
+#[test]
+fn names_may_be_shared_without_the_flag() {
+    new_test_ext().execute_with(|| {
+        let tom = insert_kitty(ALICE, [0x00; 16]);
+        let other = insert_kitty(BOB, [0x01; 16]);
+
+        assert_ok!(KittiesModule::set_name(
+            Origin::signed(ALICE),
+            tom,
+            b"Tom".to_vec()
+        ));
+        assert_ok!(KittiesModule::set_name(
+            Origin::signed(BOB),
+            other,
+            b"Tom".to_vec()
+        ));
+        assert_eq!(KittiesModule::name_owner(bounded_name(b"Tom")), None);
+    });
+}
+
+#[test]
+fn set_name_charges_rename_fee() {
+    ExtBuilder::default()
+        .rename_fee(50)
+        .build()
+        .execute_with(|| {
+            let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+
+            assert_ok!(KittiesModule::set_name(
+                Origin::signed(ALICE),
+                kitty_id,
+                b"Tom".to_vec()
+            ));
+            assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - 50);
+            assert_eq!(Balances::free_balance(FEE_COLLECTOR), 50);
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::KittyRenamed(
+                    ALICE,
+                    kitty_id,
+                    b"Tom".to_vec(),
+                    50
+                ))
+            );
+
+            // Without the fee the name stays as it was
+            let _ = Balances::make_free_balance_be(&ALICE, 20);
+            assert_noop!(
+                KittiesModule::set_name(Origin::signed(ALICE), kitty_id, b"Jerry".to_vec()),
+                pallet_balances::Error::<Test>::InsufficientBalance
+            );
+            assert_eq!(
+                KittiesModule::kitty_names(ALICE, kitty_id).map(|name| name.to_vec()),
+                Some(b"Tom".to_vec())
+            );
+        });
+}
+
+#[test]
+fn buy_moves_kitty_and_funds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KittiesModule::create(Origin::signed(ALICE)));
+
+        assert_noop!(
+            KittiesModule::buy(Origin::signed(BOB), ALICE, 0, 100),
+            Error::<Test>::NotForSale
+        );
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(ALICE),
+            0,
+            Some(100),
+            None
+        ));
+        assert_noop!(
+            KittiesModule::buy(Origin::signed(BOB), ALICE, 0, 99),
+            Error::<Test>::PriceTooHigh
+        );
+        assert_noop!(
+            KittiesModule::buy(Origin::signed(ALICE), ALICE, 0, 100),
+            Error::<Test>::BuyFromSelf
+        );
+
+        assert_ok!(KittiesModule::buy(Origin::signed(BOB), ALICE, 0, 100));
+
+        assert_eq!(KittiesModule::owner_of(0), Some(BOB));
+        assert_eq!(KittiesModule::kitty_prices(BOB, 0), None);
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 100);
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE + 100 - KITTY_DEPOSIT
+        );
+    });
+}
+
+#[test]
+fn listing_expires_at_its_expiry_block() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+        let kept_id = insert_kitty(ALICE, [0x01; 16]);
+
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(ALICE), kitty_id, Some(100), Some(1)),
+            Error::<Test>::ExpiryInPast
+        );
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(ALICE),
+            kitty_id,
+            Some(100),
+            Some(10)
+        ));
+        // Relisting without an expiry keeps the other kitty listed past block 10
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(ALICE),
+            kept_id,
+            Some(100),
+            Some(10)
+        ));
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(ALICE),
+            kept_id,
+            Some(50),
+            None
+        ));
+        assert_noop!(
+            KittiesModule::set_price(Origin::signed(ALICE), kitty_id, Some(100), Some(10)),
+            Error::<Test>::TooManyExpiringListings
+        );
+
+        run_to_block(9);
+        assert_eq!(KittiesModule::kitty_prices(ALICE, kitty_id), Some(100));
+
+        run_to_block(10);
+        assert_eq!(KittiesModule::kitty_prices(ALICE, kitty_id), None);
+        assert_eq!(KittiesModule::listing_expiry(ALICE, kitty_id), None);
+        assert_eq!(KittiesModule::kitty_prices(ALICE, kept_id), Some(50));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::ListingExpired(kitty_id))
+        );
+    });
+}
+
+#[test]
+fn buy_pays_marketplace_fee_to_collector() {
+    ExtBuilder::default()
+        .marketplace_fee(Permill::from_parts(25_000))
+        .build()
+        .execute_with(|| {
+            let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+            let other_kitty_id = insert_kitty(ALICE, [6u8; 16]);
+            KittyPrices::<Test>::insert(ALICE, kitty_id, 1_000);
+            KittyPrices::<Test>::insert(ALICE, other_kitty_id, 10);
+
+            // 2.5% of 1000
+            assert_ok!(KittiesModule::buy(
+                Origin::signed(BOB),
+                ALICE,
+                kitty_id,
+                1_000
+            ));
+            assert_eq!(Balances::free_balance(FEE_COLLECTOR), 25);
+            assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE + 975);
+            assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 1_000);
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::KittySold(ALICE, BOB, kitty_id, 1_000, 25))
+            );
+
+            // The fee of 0.25 rounds down to zero
+            assert_ok!(KittiesModule::buy(
+                Origin::signed(BOB),
+                ALICE,
+                other_kitty_id,
+                10
+            ));
+            assert_eq!(Balances::free_balance(FEE_COLLECTOR), 25);
+            assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE + 985);
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::KittySold(ALICE, BOB, other_kitty_id, 10, 0))
+            );
+        });
+}
+
+#[test]
+fn auction_goes_to_the_highest_bidder() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+
+        assert_ok!(KittiesModule::start_auction(
+            Origin::signed(ALICE),
+            kitty_id,
+            100,
+            10
+        ));
+        assert!(KittiesModule::is_locked(kitty_id));
+        assert_noop!(
+            KittiesModule::transfer(Origin::signed(ALICE), BOB, kitty_id),
+            Error::<Test>::KittyLocked
+        );
+
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(BOB), kitty_id, 99),
+            Error::<Test>::BidTooLow
+        );
+        assert_ok!(KittiesModule::bid(Origin::signed(BOB), kitty_id, 100));
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(CHARLIE), kitty_id, 100),
+            Error::<Test>::BidTooLow
+        );
+        assert_ok!(KittiesModule::bid(Origin::signed(CHARLIE), kitty_id, 150));
+
+        // The outbid bidder gets the reserve back
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 150);
+
+        assert_noop!(
+            KittiesModule::close_auction(Origin::signed(BOB), kitty_id),
+            Error::<Test>::AuctionNotEnded
+        );
+        run_to_block(11);
+        assert_noop!(
+            KittiesModule::bid(Origin::signed(BOB), kitty_id, 200),
+            Error::<Test>::AuctionEnded
+        );
+
+        assert_ok!(KittiesModule::close_auction(Origin::signed(BOB), kitty_id));
+
+        assert_eq!(KittiesModule::owner_of(kitty_id), Some(CHARLIE));
+        assert!(!KittiesModule::is_locked(kitty_id));
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE - 150);
+        assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE + 150);
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::AuctionClosed(kitty_id, Some(CHARLIE), 150))
+        );
+    });
+}
+
+#[test]
+fn auction_refunds_a_winner_without_room() {
+    ExtBuilder::default()
+        .max_kitties_owned(2)
+        .build()
+        .execute_with(|| {
+            let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+            insert_kitty(BOB, [6u8; 16]);
+            insert_kitty(CHARLIE, [7u8; 16]);
+            insert_kitty(CHARLIE, [8u8; 16]);
+            assert_ok!(KittiesModule::start_auction(
+                Origin::signed(ALICE),
+                kitty_id,
+                100,
+                10
+            ));
+
+            assert_noop!(
+                KittiesModule::bid(Origin::signed(CHARLIE), kitty_id, 100),
+                Error::<Test>::TooManyKitties
+            );
+            assert_ok!(KittiesModule::bid(Origin::signed(BOB), kitty_id, 100));
+
+            // BOB reaches the cap after bidding
+            insert_kitty(BOB, [9u8; 16]);
+            run_to_block(11);
+            assert_ok!(KittiesModule::close_auction(Origin::signed(BOB), kitty_id));
+
+            assert_eq!(KittiesModule::owner_of(kitty_id), Some(ALICE));
+            assert!(!KittiesModule::is_locked(kitty_id));
+            assert_eq!(KittiesModule::auctions(kitty_id), None);
+            assert_eq!(Balances::reserved_balance(BOB), 0);
+            assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+            assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+            assert_eq!(
+                last_event(),
+                mock::Event::from(crate::Event::AuctionClosed(kitty_id, None, 100))
+            );
+        });
+}
+
+#[test]
+fn auction_without_bids_returns_the_kitty() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+        assert_ok!(KittiesModule::start_auction(
+            Origin::signed(ALICE),
+            kitty_id,
+            100,
+            10
+        ));
+
+        run_to_block(11);
+        assert_ok!(KittiesModule::close_auction(Origin::signed(BOB), kitty_id));
+
+        assert_eq!(KittiesModule::owner_of(kitty_id), Some(ALICE));
+        assert!(!KittiesModule::is_locked(kitty_id));
+        assert_eq!(KittiesModule::auctions(kitty_id), None);
+    });
+}
+
+#[test]
+fn nonfungibles_inspect_reads_owner_and_dna() {
+    use frame_support::traits::tokens::nonfungibles::Inspect;
+
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+
+        assert_eq!(
+            <KittiesModule as Inspect<u64>>::owner(&(), &kitty_id),
+            Some(ALICE)
+        );
+        assert_eq!(
+            <KittiesModule as Inspect<u64>>::attribute(&(), &kitty_id, b"dna"),
+            Some(vec![5u8; 16])
+        );
+        assert_eq!(
+            <KittiesModule as Inspect<u64>>::attribute(&(), &kitty_id, b"name"),
+            None
+        );
+        assert_eq!(<KittiesModule as Inspect<u64>>::owner(&(), &42), None);
+    });
+}
+
+#[test]
+fn nonfungibles_transfer_moves_kitty() {
+    use frame_support::traits::tokens::nonfungibles::Transfer;
+
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+
+        assert_noop!(
+            <KittiesModule as Transfer<u64>>::transfer(&(), &42, &BOB),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_ok!(<KittiesModule as Transfer<u64>>::transfer(
+            &(),
+            &kitty_id,
+            &BOB
+        ));
+
+        assert_eq!(KittiesModule::owner_of(kitty_id), Some(BOB));
+        assert_eq!(
+            last_event(),
+            mock::Event::from(crate::Event::KittyTransferred(ALICE, BOB, kitty_id))
+        );
+    });
+}
+
+#[test]
+fn nonfungibles_are_frozen_while_paused() {
+    use frame_support::traits::tokens::nonfungibles::{Mutate, Transfer};
+
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [0x00; 16]);
+        assert_ok!(KittiesModule::set_paused(Origin::root(), true));
+
+        assert_noop!(
+            <KittiesModule as Mutate<u64>>::mint_into(&(), &5, &ALICE),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            <KittiesModule as Transfer<u64>>::transfer(&(), &kitty_id, &BOB),
+            Error::<Test>::PalletPaused
+        );
+        assert_noop!(
+            <KittiesModule as Mutate<u64>>::burn_from(&(), &kitty_id),
+            Error::<Test>::PalletPaused
+        );
+
+        assert_ok!(KittiesModule::set_paused(Origin::root(), false));
+        assert_ok!(<KittiesModule as Transfer<u64>>::transfer(
+            &(),
+            &kitty_id,
+            &BOB
+        ));
+    });
+}
+
+#[test]
+fn nonfungibles_mutate_mints_and_burns() {
+    use frame_support::traits::tokens::nonfungibles::Mutate;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&(), &5, &ALICE));
+
+        assert_eq!(KittiesModule::owner_of(5), Some(ALICE));
+        assert_eq!(KittiesModule::next_kitty_id(), 6);
+        assert_eq!(KittiesModule::kitty_count(), 1);
+        assert_eq!(KittiesModule::total_created(), 1);
+        assert_eq!(KittiesModule::account_nonce(ALICE), 1);
+        assert_eq!(Balances::reserved_balance(ALICE), KITTY_DEPOSIT);
+        assert_noop!(
+            <KittiesModule as Mutate<u64>>::mint_into(&(), &5, &BOB),
+            Error::<Test>::KittyAlreadyExists
+        );
+
+        assert_ok!(<KittiesModule as Mutate<u64>>::burn_from(&(), &5));
+
+        assert_eq!(KittiesModule::kitties(5), None);
+        assert_eq!(KittiesModule::kitty_count(), 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        let events = System::events();
+        assert_eq!(
+            events[events.len() - 2].event,
+            mock::Event::from(crate::Event::KittyBurned(ALICE, 5))
+        );
+
+        assert_noop!(
+            <KittiesModule as Mutate<u64>>::mint_into(&(), &5, &BOB),
+            Error::<Test>::KittyAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn nonfungibles_mint_follows_the_create_rules() {
+    use frame_support::traits::tokens::nonfungibles::Mutate;
+
+    ExtBuilder::default()
+        .min_block_for_creation(3)
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                <KittiesModule as Mutate<u64>>::mint_into(&(), &5, &ALICE),
+                Error::<Test>::RandomnessNotReady
+            );
+
+            run_to_block(3);
+            let seed = H256::repeat_byte(7);
+            set_random_seed(seed);
+            assert_ok!(<KittiesModule as Mutate<u64>>::mint_into(&(), &5, &ALICE));
+            assert_eq!(
+                KittiesModule::kitties(5),
+                Some(Kitty(
+                    (seed, ALICE, None::<u32>, 3u64, 0u32).using_encoded(blake2_128)
+                ))
+            );
+
+            // The same seed, block and nonce would give ALICE the DNA she already owns
+            set_random_seed(seed);
+            AccountNonce::<Test>::remove(ALICE);
+            assert_noop!(
+                <KittiesModule as Mutate<u64>>::mint_into(&(), &6, &ALICE),
+                Error::<Test>::DuplicateDnaForOwner
+            );
+        });
+}
+
+#[test]
+fn kitty_exists_and_is_owner() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+
+        assert!(KittiesModule::kitty_exists(kitty_id));
+        assert!(!KittiesModule::kitty_exists(kitty_id + 1));
+        assert!(KittiesModule::is_owner(&ALICE, kitty_id));
+        assert!(!KittiesModule::is_owner(&BOB, kitty_id));
+        assert!(!KittiesModule::is_owner(&ALICE, kitty_id + 1));
+    });
+}
+
+#[test]
+fn owner_gender_ratio_counts_owned_kitties() {
+    new_test_ext().execute_with(|| {
+        insert_kitty(ALICE, [0x00; 16]);
+        insert_kitty(ALICE, [0x02; 16]);
+        let overridden = insert_kitty(ALICE, [0x04; 16]);
+        insert_kitty(ALICE, [0x01; 16]);
+        insert_kitty(BOB, [0x03; 16]);
+
+        assert_eq!(KittiesModule::owner_gender_ratio(&ALICE), (3, 1));
+        assert_eq!(KittiesModule::owner_gender_ratio(&BOB), (0, 1));
+        assert_eq!(KittiesModule::owner_gender_ratio(&CHARLIE), (0, 0));
+
+        assert_ok!(KittiesModule::admin_set_gender(
+            Origin::root(),
+            overridden,
+            KittyGender::Female
+        ));
+        assert_eq!(KittiesModule::owner_gender_ratio(&ALICE), (2, 2));
+    });
+}
+
+#[test]
+fn rarity_stats_cover_all_kitties() {
+    new_test_ext().execute_with(|| {
+        insert_kitty(ALICE, [0x00; 16]);
+        insert_kitty(BOB, [0xff; 16]);
+        insert_kitty(BOB, [0xf1; 16]);
+
+        assert_eq!(
+            KittiesModule::rarity_stats(),
+            RarityStats {
+                count: 3,
+                males: 1,
+                females: 2,
+                total_rarity: 48,
+                max_rarity: 32,
+            }
+        );
+    });
+}
+
+#[test]
+fn offchain_worker_reports_once_per_interval() {
+    let mut ext = new_test_ext();
+    let (offchain, _state) = TestOffchainExt::new();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+
+    ext.execute_with(|| {
+        let last_report = || {
+            StorageValueRef::persistent(OCW_LAST_REPORT_KEY)
+                .get::<u64>()
+                .unwrap()
+        };
+
+        <KittiesModule as OffchainWorker<u64>>::offchain_worker(1);
+        assert_eq!(last_report(), Some(1));
+
+        <KittiesModule as OffchainWorker<u64>>::offchain_worker(5);
+        assert_eq!(last_report(), Some(1));
+
+        <KittiesModule as OffchainWorker<u64>>::offchain_worker(6);
+        assert_eq!(last_report(), Some(6));
+    });
+}
+
+#[test]
+fn kitty_by_id_ignores_owner() {
+    new_test_ext().execute_with(|| {
+        let kitty_id = insert_kitty(ALICE, [5u8; 16]);
+        assert_eq!(KittiesModule::kitty_by_id(kitty_id), Some(Kitty([5u8; 16])));
+
+        assert_ok!(KittiesModule::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            kitty_id
+        ));
+        assert_eq!(KittiesModule::kitty_by_id(kitty_id), Some(Kitty([5u8; 16])));
+        assert_eq!(KittiesModule::kitty_by_id(kitty_id + 1), None);
+    });
+}
+
+#[test]
+fn kitty_info_bundles_metadata() {
+    new_test_ext().execute_with(|| {
+        let male = insert_kitty(ALICE, [0x00; 16]);
+        let female = insert_kitty(ALICE, [0xff; 16]);
+        run_to_block(3);
+        assert_ok!(KittiesModule::breed(Origin::signed(ALICE), male, female));
+
+        let child = KittiesModule::kitties(2).unwrap();
+        assert_eq!(
+            KittiesModule::kitty_info(&ALICE, 2),
+            Some(KittyInfo {
+                dna: child.0,
+                gender: child.gender(),
+                generation: 1,
+                birth_block: Some(3),
+                parents: Some((male, female)),
+            })
+        );
+        assert_eq!(KittiesModule::kitty_info(&BOB, 2), None);
+        assert_eq!(KittiesModule::kitty_info(&ALICE, 3), None);
+    });
+}
+
+#[test]
+fn all_kitty_ids_pages_through_existing_kitties() {
+    new_test_ext().execute_with(|| {
+        for step in 1..=5 {
+            insert_kitty(if step % 2 == 0 { BOB } else { ALICE }, dna_with_step(step));
+        }
+        assert_ok!(KittiesModule::burn(Origin::signed(ALICE), 2));
+
+        assert_eq!(KittiesModule::all_kitty_ids(0, 2), vec![0, 1]);
+        assert_eq!(KittiesModule::all_kitty_ids(2, 2), vec![3, 4]);
+        assert_eq!(KittiesModule::all_kitty_ids(5, 2), Vec::<u32>::new());
+        assert_eq!(KittiesModule::all_kitty_ids(0, 10), vec![0, 1, 3, 4]);
+    });
+}
+
+#[test]
+fn listed_kitties_returns_every_listing_with_its_price() {
+    new_test_ext().execute_with(|| {
+        let first = insert_kitty(ALICE, dna_with_step(1));
+        insert_kitty(ALICE, dna_with_step(2));
+        let second = insert_kitty(BOB, dna_with_step(3));
+
+        assert_eq!(KittiesModule::listed_kitties(0, 10), vec![]);
+
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(BOB),
+            second,
+            Some(300),
+            None
+        ));
+        assert_ok!(KittiesModule::set_price(
+            Origin::signed(ALICE),
+            first,
+            Some(100),
+            None
+        ));
+
+        assert_eq!(
+            KittiesModule::listed_kitties(0, 10),
+            vec![(first, ALICE, 100), (second, BOB, 300)]
+        );
+        assert_eq!(
+            KittiesModule::listed_kitties(0, 1),
+            vec![(first, ALICE, 100)]
+        );
+        assert_eq!(
+            KittiesModule::listed_kitties(first + 1, 10),
+            vec![(second, BOB, 300)]
+        );
+    });
+}
+
+#[test]
+fn kitties_paged_walks_owned_kitties() {
+    new_test_ext().execute_with(|| {
+        for step in 1..=5 {
+            insert_kitty(ALICE, dna_with_step(step));
+        }
+        insert_kitty(BOB, [0x00; 16]);
+
+        let (page, next) = KittiesModule::kitties_paged(&ALICE, None, 2);
+        assert_eq!(
+            page,
+            vec![(0, Kitty(dna_with_step(1))), (1, Kitty(dna_with_step(2)))]
+        );
+        assert_eq!(next, Some(2));
+
+        let (page, next) = KittiesModule::kitties_paged(&ALICE, next, 2);
+        assert_eq!(
+            page.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(next, Some(4));
+
+        let (page, next) = KittiesModule::kitties_paged(&ALICE, next, 2);
+        assert_eq!(page, vec![(4, Kitty(dna_with_step(5)))]);
+        assert_eq!(next, None);
+    });
+}
+
+#[test]
+fn migration_to_v1_moves_kitties_to_global_ids() {
+    use frame_support::{
+        storage::unhashed,
+        traits::{GetStorageVersion, StorageVersion},
+        Hashable,
+    };
+    use sp_io::hashing::twox_128;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<KittiesModule>();
+
+        // `Kitties` used to be a double map keyed by (owner, kitty_id).
+        let mut key = twox_128(b"KittiesModule").to_vec();
+        key.extend_from_slice(&twox_128(b"Kitties"));
+        key.extend_from_slice(&ALICE.blake2_128_concat());
+        key.extend_from_slice(&7u32.blake2_128_concat());
+        unhashed::put(&key, &Kitty([3u8; 16]));
+
+        migrations::v1::migrate::<Test>();
+
+        assert_eq!(unhashed::get::<Kitty>(&key), None);
+        assert_eq!(KittiesModule::kitties(7), Some(Kitty([3u8; 16])));
+        assert_eq!(KittiesModule::owner_of(7), Some(ALICE));
+        assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+    });
+}
+
+#[test]
+fn migration_abandons_kitties_beyond_the_owner_cap() {
+    use frame_support::{storage::unhashed, traits::StorageVersion, Hashable};
+    use sp_io::hashing::twox_128;
+
+    ExtBuilder::default()
+        .max_kitties_owned(1)
+        .build()
+        .execute_with(|| {
+            StorageVersion::new(0).put::<KittiesModule>();
+            for (kitty_id, dna) in &[(7u32, [0x02u8; 16]), (8, [0x03; 16])] {
+                let mut key = twox_128(b"KittiesModule").to_vec();
+                key.extend_from_slice(&twox_128(b"Kitties"));
+                key.extend_from_slice(&ALICE.blake2_128_concat());
+                key.extend_from_slice(&kitty_id.blake2_128_concat());
+                unhashed::put(&key, &Kitty(*dna));
+            }
+
+            migrations::v1::migrate::<Test>();
+
+            // The old map is drained in hash order, either kitty may be the one kept
+            assert_eq!(KittiesModule::owned_count(ALICE), 1);
+            let kept = KittiesModule::owned(ALICE)[0];
+            let left = if kept == 7 { 8 } else { 7 };
+            assert_eq!(KittiesModule::owner_of(kept), Some(ALICE));
+            assert_eq!(KittiesModule::owner_of(left), None);
+            assert!(KittiesModule::kitties(left).is_none());
+            assert!(KittiesModule::abandoned(left).is_some());
+            assert_eq!(KittiesModule::next_kitty_id(), 9);
+
+            // Nothing of the abandoned kitty is counted for its old owner
+            assert_eq!(KittiesModule::kitty_count(), 1);
+            assert_eq!(
+                KittiesModule::male_count() + KittiesModule::female_count(),
+                1
+            );
+            assert_eq!(KittiesModule::history_of(left).len(), 0);
+            assert_eq!(KittiesModule::owner_index_of(&ALICE, left), None);
+
+            assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, kept));
+            assert_ok!(KittiesModule::claim(Origin::signed(ALICE), left));
+            assert_ok!(KittiesModule::burn(Origin::signed(ALICE), left));
+            assert_eq!(KittiesModule::kitty_count(), 1);
+        });
+}
+
+#[test]
+fn migrated_kitty_can_be_transferred_and_burned() {
+    use frame_support::{storage::unhashed, traits::StorageVersion, Hashable};
+    use sp_io::hashing::twox_128;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<KittiesModule>();
+        let mut key = twox_128(b"KittiesModule").to_vec();
+        key.extend_from_slice(&twox_128(b"Kitties"));
+        key.extend_from_slice(&ALICE.blake2_128_concat());
+        key.extend_from_slice(&7u32.blake2_128_concat());
+        unhashed::put(&key, &Kitty([3u8; 16]));
+
+        migrations::v1::migrate::<Test>();
+
+        assert_eq!(KittiesModule::owned_count(ALICE), 1);
+        assert_eq!(KittiesModule::owned(ALICE).to_vec(), vec![7]);
+        assert_eq!(KittiesModule::kitty_count(), 1);
+        assert_eq!(KittiesModule::female_count(), 1);
+        assert_eq!(KittiesModule::next_kitty_id(), 8);
+        assert_eq!(KittiesModule::owner_index_of(&ALICE, 7), Some(0));
+        assert!(OwnerDna::<Test>::contains_key(ALICE, [3u8; 16]));
+        assert!(DnaExists::<Test>::contains_key([3u8; 16]));
+
+        assert_ok!(KittiesModule::transfer(Origin::signed(ALICE), BOB, 7));
+        assert_eq!(KittiesModule::owned_count(ALICE), 0);
+        assert_ok!(KittiesModule::burn(Origin::signed(BOB), 7));
+        assert_eq!(KittiesModule::kitty_count(), 0);
+        assert_eq!(KittiesModule::owned_count(BOB), 0);
+    });
+}
+
+#[test]
+fn migrations_skip_up_to_date_storage() {
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<KittiesModule>();
+        let kitty_id = insert_kitty(ALICE, [3u8; 16]);
+
+        <KittiesModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+        assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+        assert_eq!(KittiesModule::kitties(kitty_id), Some(Kitty([3u8; 16])));
+        assert_eq!(KittiesModule::owner_of(kitty_id), Some(ALICE));
+    });
+}
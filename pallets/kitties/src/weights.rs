@@ -0,0 +1,454 @@
+//! Weights for pallet-kitties.
+//!
+//! The values below are counted by hand from the storage accesses of each call until the
+//! benchmarks in `benchmarking.rs` are run against reference hardware, after which this file
+//! should be regenerated.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet-kitties.
+pub trait WeightInfo {
+    fn create() -> Weight;
+    fn breed() -> Weight;
+    fn transfer() -> Weight;
+    fn burn(a: u32) -> Weight;
+    fn set_name() -> Weight;
+    fn set_price() -> Weight;
+    fn buy() -> Weight;
+    fn approve() -> Weight;
+    fn transfer_from() -> Weight;
+    fn create_batch(n: u32) -> Weight;
+    fn admin_set_gender() -> Weight;
+    fn approve_breeding() -> Weight;
+    fn breed_with() -> Weight;
+    fn set_breeding_paused() -> Weight;
+    fn transfer_all(n: u32) -> Weight;
+    fn start_auction() -> Weight;
+    fn bid() -> Weight;
+    fn close_auction() -> Weight;
+    fn breed_precheck() -> Weight;
+    fn set_sterile() -> Weight;
+    fn breed_batch(n: u32) -> Weight;
+    fn gift_random() -> Weight;
+    fn abandon() -> Weight;
+    fn claim() -> Weight;
+    fn fuse() -> Weight;
+    fn approve_swap() -> Weight;
+    fn swap() -> Weight;
+    fn on_initialize(n: u32) -> Weight;
+    fn force_transfer() -> Weight;
+    fn reserve_for_breeding() -> Weight;
+    fn set_attribute() -> Weight;
+    fn clear_attribute() -> Weight;
+    fn set_paused() -> Weight;
+    fn cleanup_dead_kitties(n: u32) -> Weight;
+    fn transfer_many(n: u32) -> Weight;
+}
+
+/// Weights for pallet-kitties using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn breed() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn transfer() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(15 as Weight))
+            .saturating_add(T::DbWeight::get().writes(21 as Weight))
+    }
+    fn burn(a: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes(34 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
+    }
+    fn set_name() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_price() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn buy() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn approve() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_from() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn create_batch(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+    }
+    fn admin_set_gender() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn approve_breeding() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn breed_with() -> Weight {
+        (65_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(14 as Weight))
+    }
+    fn set_breeding_paused() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(0 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_all(n: u32) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((30_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn start_auction() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn bid() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn close_auction() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn breed_precheck() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(0 as Weight))
+    }
+    fn set_sterile() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn breed_batch(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((110_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((17 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(0 as Weight))
+            .saturating_add(T::DbWeight::get().writes((22 as Weight).saturating_mul(n as Weight)))
+    }
+    fn gift_random() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn abandon() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn claim() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn fuse() -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(29 as Weight))
+            .saturating_add(T::DbWeight::get().writes(30 as Weight))
+    }
+    fn approve_swap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn swap() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(20 as Weight))
+    }
+    fn on_initialize(n: u32) -> Weight {
+        (2_000_000 as Weight)
+            .saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+    }
+    fn force_transfer() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+    }
+    fn reserve_for_breeding() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn set_attribute() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn clear_attribute() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_paused() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(0 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn cleanup_dead_kitties(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+    }
+    fn transfer_many(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((40_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(0 as Weight))
+            .saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(0 as Weight))
+            .saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn breed() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn transfer() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(21 as Weight))
+    }
+    fn burn(a: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(34 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
+    }
+    fn set_name() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_price() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn buy() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn approve() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_from() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn create_batch(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(n as Weight)))
+    }
+    fn admin_set_gender() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn approve_breeding() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn breed_with() -> Weight {
+        (65_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(14 as Weight))
+    }
+    fn set_breeding_paused() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(0 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn transfer_all(n: u32) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((30_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn start_auction() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn bid() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn close_auction() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn breed_precheck() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(0 as Weight))
+    }
+    fn set_sterile() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn breed_batch(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((110_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((17 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(0 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((22 as Weight).saturating_mul(n as Weight)))
+    }
+    fn gift_random() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn abandon() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn claim() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn fuse() -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(29 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(30 as Weight))
+    }
+    fn approve_swap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn swap() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(20 as Weight))
+    }
+    fn on_initialize(n: u32) -> Weight {
+        (2_000_000 as Weight)
+            .saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+    }
+    fn force_transfer() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+    }
+    fn reserve_for_breeding() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn set_attribute() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn clear_attribute() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_paused() -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(0 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn cleanup_dead_kitties(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+    }
+    fn transfer_many(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((40_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(0 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(0 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+}
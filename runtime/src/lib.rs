@@ -14,6 +14,7 @@ use sp_runtime::{
 };
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, AccountIdLookup, Verify, IdentifyAccount, NumberFor,
+	AccountIdConversion,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -30,8 +31,8 @@ pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
-	construct_runtime, parameter_types, StorageValue,
-	traits::{KeyOwnerProofSystem, Randomness},
+	construct_runtime, parameter_types, PalletId, StorageValue,
+	traits::{KeyOwnerProofSystem, Randomness, SortedMembers},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -272,8 +273,87 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const MaxNameLength: u32 = 32;
+	pub const MaxKeyLen: u32 = 32;
+	pub const MaxValueLen: u32 = 64;
+	pub const MaxAttributes: u32 = 16;
+	pub const MaxKittiesOwned: u32 = 100;
+	pub const KittyDeposit: Balance = 1_000_000_000_000;
+	pub const BreedingDeposit: Balance = 1_000_000_000_000;
+	pub const MinCreatorBalance: Balance = 10_000_000_000_000;
+	pub const MinBlockForCreation: BlockNumber = 81;
+	pub const IdWarningThreshold: u32 = u32::MAX - 1_000_000;
+	pub const BreedCooldownPeriod: BlockNumber = 10 * MINUTES;
+	pub const MaxBatchSize: u32 = 20;
+	pub const GraveyardRetention: BlockNumber = 7 * DAYS;
+	pub const MutationRate: u8 = 4;
+	pub const MaxHistory: u32 = 20;
+	pub const MaxSupply: u32 = u32::MAX;
+	pub const OcwInterval: u32 = 10;
+	pub const MaxLitterSize: u32 = 2;
+	pub const ForbidSameColorBreeding: bool = false;
+	pub const MaturityPeriod: BlockNumber = HOURS;
+	pub const MaxBreedings: u32 = 10;
+	pub const MinBreedingGeneration: u32 = 0;
+	pub const MarketplaceFee: Permill = Permill::from_percent(2);
+	pub const UniqueNames: bool = false;
+	pub const RenameFee: Balance = 100_000_000_000;
+	pub const BreedFee: Balance = 100_000_000_000;
+	pub const BurnRatio: Permill = Permill::from_percent(50);
+	pub const KittiesPalletId: PalletId = PalletId(*b"py/kitty");
+	pub KittiesFeeCollector: AccountId = KittiesPalletId::get().into_account();
+	pub const MaxExpiringPerBlock: u32 = 50;
+}
+
+/// The sudo key is the only account allowed to gift kitties.
+pub struct SudoKey;
+impl SortedMembers<AccountId> for SudoKey {
+	fn sorted_members() -> Vec<AccountId> {
+		vec![Sudo::key()]
+	}
+}
+
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
+	type Randomness = RandomnessCollectiveFlip;
+	type MaxNameLength = MaxNameLength;
+	type MaxKeyLen = MaxKeyLen;
+	type MaxValueLen = MaxValueLen;
+	type MaxAttributes = MaxAttributes;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type Currency = Balances;
+	type KittyDeposit = KittyDeposit;
+	type BreedingDeposit = BreedingDeposit;
+	type MinCreatorBalance = MinCreatorBalance;
+	type MinBlockForCreation = MinBlockForCreation;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+	type IdWarningThreshold = IdWarningThreshold;
+	type BreedCooldownPeriod = BreedCooldownPeriod;
+	type MaxBatchSize = MaxBatchSize;
+	type GraveyardRetention = GraveyardRetention;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type DnaMixer = pallet_kitties::BitmaskMixer;
+	type MutationRate = MutationRate;
+	type OnKittyCreated = ();
+	type PauseOrigin = frame_system::EnsureRoot<AccountId>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxHistory = MaxHistory;
+	type MaxSupply = MaxSupply;
+	type OcwInterval = OcwInterval;
+	type GiftOrigin = frame_system::EnsureSignedBy<SudoKey, AccountId>;
+	type MaxLitterSize = MaxLitterSize;
+	type ForbidSameColorBreeding = ForbidSameColorBreeding;
+	type MaturityPeriod = MaturityPeriod;
+	type MaxBreedings = MaxBreedings;
+	type MinBreedingGeneration = MinBreedingGeneration;
+	type MarketplaceFee = MarketplaceFee;
+	type FeeCollector = KittiesFeeCollector;
+	type UniqueNames = UniqueNames;
+	type RenameFee = RenameFee;
+	type BreedFee = BreedFee;
+	type BurnRatio = BurnRatio;
+	type MaxExpiringPerBlock = MaxExpiringPerBlock;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -294,7 +374,7 @@ construct_runtime!(
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
 		// Substrate Kitties pallet
-		Kitties: pallet_kitties::{Pallet, Storage, Event<T>},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Event<T>, Config<T>},
 	}
 );
 
@@ -452,6 +532,56 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties::runtime_api::KittiesApi<Block, AccountId, BlockNumber, Balance> for Runtime {
+		fn kitty_info(
+			owner: AccountId,
+			kitty_id: u32,
+		) -> Option<pallet_kitties::KittyInfo<BlockNumber>> {
+			Kitties::kitty_info(&owner, kitty_id)
+		}
+
+		fn kitties_paged(
+			owner: AccountId,
+			start_key: Option<u32>,
+			limit: u32,
+		) -> (Vec<(u32, pallet_kitties::Kitty)>, Option<u32>) {
+			Kitties::kitties_paged(&owner, start_key, limit)
+		}
+
+		fn describe_kitty(owner: AccountId, kitty_id: u32) -> Option<Vec<u8>> {
+			Kitties::describe_kitty(&owner, kitty_id)
+		}
+
+		fn owner_gender_ratio(owner: AccountId) -> (u32, u32) {
+			Kitties::owner_gender_ratio(&owner)
+		}
+
+		fn owned_count(owner: AccountId) -> u32 {
+			Kitties::owned_count(&owner)
+		}
+
+		fn breeding_compatibility(kitty_id_1: u32, kitty_id_2: u32) -> Option<u32> {
+			Kitties::breeding_compatibility(kitty_id_1, kitty_id_2)
+		}
+
+		fn all_kitty_ids(start: u32, limit: u32) -> Vec<u32> {
+			Kitties::all_kitty_ids(start, limit)
+		}
+
+		fn listed_kitties(start: u32, limit: u32) -> Vec<(u32, AccountId, Balance)> {
+			Kitties::listed_kitties(start, limit)
+		}
+
+		fn simulate_breed(
+			owner: AccountId,
+			kitty_id_1: u32,
+			kitty_id_2: u32,
+			selector: [u8; 16],
+		) -> Result<pallet_kitties::Kitty, sp_runtime::DispatchError> {
+			Kitties::simulate_breed(&owner, kitty_id_1, kitty_id_2, selector).map_err(Into::into)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -482,6 +612,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
+			add_benchmark!(params, batches, pallet_kitties, Kitties);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)